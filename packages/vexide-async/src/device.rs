@@ -0,0 +1,126 @@
+//! Waiting for updates from Smart devices.
+//!
+//! VEXos reads a new packet from each Smart device at a fixed interval (usually every 10
+//! milliseconds). Rather than sleeping for that interval and hoping that fresh data has arrived,
+//! tasks can wait for the next packet from a device using [`next_update`].
+//!
+//! Most users will want to use `SmartDevice::next_update` from `vexide::smart` instead, which calls
+//! this function with the device's port.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use vex_sdk::{vexDeviceGetByIndex, vexDeviceGetTimestamp};
+use vexide_core::time::LowResolutionTime;
+
+use crate::{executor::EXECUTOR, reactor::DeviceWaiterKey};
+
+/// Returns the timestamp of the last packet processed from the Smart Port at `index`.
+pub(crate) fn device_timestamp(index: u32) -> u32 {
+    unsafe { vexDeviceGetTimestamp(vexDeviceGetByIndex(index)) }
+}
+
+/// A future that completes once VEXos processes a new packet from a Smart device.
+///
+/// This type is returned by the [`next_update`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NextUpdate {
+    index: u32,
+    last_timestamp: u32,
+    waiter: Option<DeviceWaiterKey>,
+}
+
+impl NextUpdate {
+    /// Removes this future's waiter from the reactor, if it has one.
+    fn deregister(&mut self) {
+        if let Some(key) = self.waiter.take() {
+            // The executor may have already been destroyed if this is being dropped along with it.
+            _ = EXECUTOR.try_with(|ex| ex.remove_device_waiter(key));
+        }
+    }
+}
+
+impl Future for NextUpdate {
+    type Output = LowResolutionTime;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let timestamp = device_timestamp(this.index);
+        if timestamp != this.last_timestamp {
+            this.deregister();
+            return Poll::Ready(LowResolutionTime::from_millis_since_epoch(timestamp));
+        }
+
+        // As with `Sleep`, the waiter is updated in place if we already have one, in case this
+        // future has been moved to a different task since it was last polled.
+        EXECUTOR.with(|ex| {
+            ex.with_reactor(|reactor| match this.waiter {
+                Some(key) if reactor.update_device_waiter(key, cx.waker()) => {}
+                _ => {
+                    this.waiter = Some(reactor.insert_device_waiter(
+                        this.index,
+                        this.last_timestamp,
+                        cx.waker().clone(),
+                    ));
+                }
+            });
+        });
+
+        Poll::Pending
+    }
+}
+
+impl Drop for NextUpdate {
+    fn drop(&mut self) {
+        self.deregister();
+    }
+}
+
+/// Waits until VEXos processes a new packet from the Smart device on the port at `index`.
+///
+/// Ports are indexed starting from 0, so port 1 has an index of 0. The returned future completes
+/// with the timestamp of the new packet.
+///
+/// Only packets received after this function is called are counted. If no device is plugged into
+/// the port, the future may never complete.
+pub fn next_update(index: u32) -> NextUpdate {
+    NextUpdate {
+        index,
+        last_timestamp: device_timestamp(index),
+        waiter: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::{block_on, time::timeout};
+
+    #[test]
+    fn waits_for_new_packet() {
+        // The mocked device never sends any packets.
+        let result = block_on(timeout(Duration::from_millis(2), next_update(0)));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn dropped_waiters_deregister() {
+        block_on(async {
+            for _ in 0..100 {
+                _ = timeout(Duration::from_millis(1), next_update(0)).await;
+            }
+        });
+
+        let waiters = EXECUTOR.with(|ex| ex.with_reactor(|reactor| reactor.device_waiter_count()));
+        assert_eq!(waiters, 0);
+    }
+}
@@ -1,22 +1,26 @@
 use std::{
-    cell::RefCell,
-    collections::VecDeque,
+    cell::{Cell, RefCell},
     future::Future,
-    rc::Rc,
+    panic::Location,
+    rc::{Rc, Weak},
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use vexide_core::backtrace::Backtrace;
 use waker_fn::waker_fn;
 
-use super::reactor::Reactor;
+use super::{
+    queue::RunQueue,
+    reactor::{DeviceWaiterKey, Reactor, TimerKey},
+};
 use crate::{
     local::TaskLocalStorage,
-    task::{Task, TaskMetadata},
+    task::{ExecutorStats, Priority, Task, TaskId, TaskInfo, TaskMetadata, TaskSnapshot},
 };
 
 type Runnable = async_task::Runnable<TaskMetadata>;
@@ -26,23 +30,59 @@ thread_local! {
 }
 
 pub(crate) struct Executor {
-    queue: RefCell<VecDeque<Runnable>>,
+    queue: RefCell<RunQueue<Runnable>>,
     reactor: RefCell<Reactor>,
     pub(crate) tls: RefCell<Option<Rc<TaskLocalStorage>>>,
+    /// Every spawned task, in the order they were spawned. Entries are removed lazily after their
+    /// task is destroyed.
+    tasks: RefCell<Vec<Weak<TaskInfo>>>,
+    next_task_id: Cell<u64>,
+    /// Polls that take longer than this are reported by the slow poll watchdog.
+    pub(crate) slow_poll_threshold: Cell<Option<Duration>>,
+    /// Called whenever the executor has nothing to do.
+    idle_hook: RefCell<Option<Box<dyn FnMut()>>>,
+    /// Incremented whenever the idle hook is set or removed, so that a running hook can tell
+    /// whether it was replaced.
+    idle_hook_generation: Cell<u64>,
+    pub(crate) stats: Cell<ExecutorStats>,
 }
 
 impl Executor {
     pub const fn new() -> Self {
         Self {
-            queue: RefCell::new(VecDeque::new()),
+            queue: RefCell::new(RunQueue::new()),
             reactor: RefCell::new(Reactor::new()),
             tls: RefCell::new(None),
+            tasks: RefCell::new(Vec::new()),
+            next_task_id: Cell::new(0),
+            slow_poll_threshold: Cell::new(None),
+            idle_hook: RefCell::new(None),
+            idle_hook_generation: Cell::new(0),
+            stats: Cell::new(ExecutorStats::new()),
         }
     }
 
-    pub fn spawn<T>(&self, future: impl Future<Output = T> + 'static) -> Task<T> {
+    pub fn spawn<T>(
+        &self,
+        future: impl Future<Output = T> + 'static,
+        name: Option<String>,
+        priority: Priority,
+        location: &'static Location<'static>,
+    ) -> Task<T> {
+        let id = TaskId(self.next_task_id.get());
+        self.next_task_id.set(id.0 + 1);
+
+        let spawn_backtrace = self.slow_poll_threshold.get().map(|_| Backtrace::capture());
+        let info = Rc::new(TaskInfo::new(id, name, priority, location, spawn_backtrace));
+        {
+            let mut tasks = self.tasks.borrow_mut();
+            tasks.retain(|task| task.strong_count() > 0);
+            tasks.push(Rc::downgrade(&info));
+        }
+
         let metadata = TaskMetadata {
             tls: Rc::new(TaskLocalStorage::new()),
+            info,
         };
 
         // SAFETY: `runnable` will never be moved off this thread or shared with another thread
@@ -55,38 +95,88 @@ impl Executor {
                 .metadata(metadata)
                 .spawn_unchecked(
                     move |_| future,
-                    |runnable| {
-                        self.queue.borrow_mut().push_back(runnable);
+                    |runnable: Runnable| {
+                        let info = &runnable.metadata().info;
+                        info.record_wake(Instant::now());
+
+                        let priority = info.priority;
+                        self.queue.borrow_mut().push(runnable, priority);
                     },
                 )
         };
 
-        runnable.schedule();
+        // The task is queued directly rather than through `schedule`, so that being spawned isn't
+        // recorded as a wake.
+        self.queue.borrow_mut().push(runnable, priority);
 
         task
     }
 
+    /// Returns a snapshot of every task that hasn't been destroyed yet.
+    pub fn snapshot(&self) -> Vec<TaskSnapshot> {
+        self.tasks
+            .borrow()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|info| info.snapshot())
+            .collect()
+    }
+
     /// Run the provided closure with the reactor.
     /// Used to ensure the thread safety of the executor.
-    pub(crate) fn with_reactor(&self, f: impl FnOnce(&mut Reactor)) {
-        f(&mut self.reactor.borrow_mut());
+    pub(crate) fn with_reactor<R>(&self, f: impl FnOnce(&mut Reactor) -> R) -> R {
+        f(&mut self.reactor.borrow_mut())
     }
 
-    /// Wakes any expired sleepers, then polls a single task. If all tasks were sleeping,
-    /// returns how long it will be until one is awake.
+    /// Deregisters a timer from the reactor.
+    ///
+    /// Unlike [`Executor::with_reactor`], this never panics, since it is called when dropping
+    /// [`Sleep`](crate::time::Sleep) futures. If the reactor is already in use, the timer is left
+    /// to expire on its own.
+    pub(crate) fn remove_timer(&self, key: TimerKey) {
+        if let Ok(mut reactor) = self.reactor.try_borrow_mut() {
+            reactor.remove_timer(key);
+        }
+    }
+
+    /// Deregisters a device waiter from the reactor.
+    ///
+    /// Like [`Executor::remove_timer`], this never panics, since it is called when dropping
+    /// [`NextUpdate`](crate::device::NextUpdate) futures. If the reactor is already in use, the
+    /// waiter is left to be woken on its own.
+    pub(crate) fn remove_device_waiter(&self, key: DeviceWaiterKey) {
+        if let Ok(mut reactor) = self.reactor.try_borrow_mut() {
+            reactor.remove_device_waiter(key);
+        }
+    }
+
+    /// Wakes any expired timers, then polls the highest-priority ready task. If all tasks were
+    /// sleeping, returns how long it will be until one is awake.
     pub(crate) fn tick(&self) -> Option<Duration> {
         let next_wake = self.reactor.borrow_mut().tick();
 
         let runnable = {
             let mut queue = self.queue.borrow_mut();
-            queue.pop_front()
+            queue.pop()
         };
 
         if let Some(runnable) = runnable {
+            let info = runnable.metadata().info.clone();
+            let start = Instant::now();
+
             TaskLocalStorage::scope(runnable.metadata().tls.clone(), || {
                 runnable.run();
             });
 
+            let elapsed = start.elapsed();
+            info.record_poll(elapsed);
+
+            if let Some(threshold) = self.slow_poll_threshold.get()
+                && elapsed > threshold
+            {
+                report_slow_poll(&info, elapsed);
+            }
+
             None
         } else {
             Some(next_wake)
@@ -104,7 +194,14 @@ impl Executor {
         futures_util::pin_mut!(future);
 
         let mut was_woken = true;
+        let mut last_iteration: Option<Instant> = None;
         loop {
+            let iteration_start = Instant::now();
+            self.record_iteration(last_iteration.map(|last| iteration_start - last));
+            last_iteration = Some(iteration_start);
+
+            // Polling the future counts as work, even if no tasks turn out to be ready.
+            let polled_future = was_woken;
             if was_woken && let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
                 return output;
             }
@@ -118,6 +215,30 @@ impl Executor {
             // future in that time.
             was_woken = woken.swap(false, Ordering::Relaxed);
 
+            if next_wake.is_some() {
+                if !polled_future {
+                    self.record_idle();
+                }
+
+                if !was_woken {
+                    self.run_idle_hook();
+                }
+            }
+
+            // While the clock is paused, there's no point in waiting for sleeping tasks, so skip
+            // straight to the next deadline instead.
+            #[cfg(feature = "test-util")]
+            if crate::time::is_paused()
+                && let Some(next_wake) = next_wake
+                && !was_woken
+            {
+                if next_wake != Duration::MAX {
+                    crate::time::advance(next_wake);
+                }
+
+                continue;
+            }
+
             // Yield to OS on desktop platforms to avoid high CPU usage while all tasks are
             // sleeping. On VEXos, this behavior is disabled so that devices are updated
             // as fast as possible.
@@ -138,6 +259,66 @@ impl Executor {
     }
 }
 
+impl Executor {
+    /// Records the start of an iteration of the executor loop, along with how long the previous
+    /// iteration took.
+    fn record_iteration(&self, previous: Option<Duration>) {
+        let mut stats = self.stats.get();
+        stats.iterations += 1;
+        if let Some(previous) = previous {
+            stats.elapsed += previous;
+        }
+        self.stats.set(stats);
+    }
+
+    /// Records that no tasks were ready during the current iteration of the executor loop.
+    fn record_idle(&self) {
+        let mut stats = self.stats.get();
+        stats.idle_iterations += 1;
+        self.stats.set(stats);
+    }
+
+    /// Replaces the idle hook, returning the previous one.
+    pub(crate) fn replace_idle_hook(
+        &self,
+        hook: Option<Box<dyn FnMut()>>,
+    ) -> Option<Box<dyn FnMut()>> {
+        self.idle_hook_generation
+            .set(self.idle_hook_generation.get() + 1);
+        self.idle_hook.replace(hook)
+    }
+
+    fn run_idle_hook(&self) {
+        // The hook is taken out while it runs so that it can spawn tasks or replace itself.
+        let Some(mut hook) = self.idle_hook.take() else {
+            return;
+        };
+        let generation = self.idle_hook_generation.get();
+
+        hook();
+
+        // Only put the hook back if it didn't replace or remove itself while running.
+        if self.idle_hook_generation.get() == generation {
+            *self.idle_hook.borrow_mut() = Some(hook);
+        }
+    }
+}
+
+/// Reports a task that blocked the executor for too long to the serial console.
+fn report_slow_poll(info: &TaskInfo, elapsed: Duration) {
+    let name = info.name.as_deref().unwrap_or("<unnamed>");
+
+    eprintln!(
+        "warning: task {} (`{name}`, spawned at {}) ran for {elapsed:?} without yielding",
+        info.id, info.location,
+    );
+    if let Some(backtrace) = &info.spawn_backtrace
+        && !backtrace.frames().is_empty()
+    {
+        eprintln!("task was spawned from:\n{backtrace}");
+    }
+}
+
 #[cfg(test)]
 mod test {
     use vex_sdk_mock as _;
@@ -148,8 +329,104 @@ mod test {
     fn spawns_task() {
         let executor = Executor::new();
 
-        let result = executor.block_on(executor.spawn(async { 1 }));
+        let result = executor.block_on(executor.spawn(
+            async { 1 },
+            None,
+            Priority::Normal,
+            Location::caller(),
+        ));
 
         assert_eq!(result, 1);
     }
+
+    #[test]
+    fn snapshots_live_tasks() {
+        let executor = Executor::new();
+
+        let mut task = executor.spawn(
+            async { 1 },
+            Some("named".to_owned()),
+            Priority::High,
+            Location::caller(),
+        );
+        assert_eq!(executor.block_on(&mut task), 1);
+
+        let snapshot = executor.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].name.as_deref(), Some("named"));
+        assert_eq!(snapshot[0].priority, Priority::High);
+        assert_eq!(snapshot[0].poll_count, 1);
+
+        drop(task);
+        assert!(executor.snapshot().is_empty());
+    }
+
+    #[test]
+    fn spawning_is_not_a_wake() {
+        let executor = Executor::new();
+
+        let task = executor.spawn(async {}, None, Priority::Normal, Location::caller());
+        assert_eq!(executor.snapshot()[0].last_woken, None);
+
+        executor.block_on(task);
+    }
+
+    #[test]
+    fn tracks_idle_time() {
+        use crate::{block_on, task, time::sleep};
+
+        let hook_calls = Rc::new(Cell::new(0));
+        task::set_idle_hook({
+            let hook_calls = hook_calls.clone();
+            move || hook_calls.set(hook_calls.get() + 1)
+        });
+
+        block_on(sleep(Duration::from_millis(5)));
+        assert!(task::take_idle_hook().is_some());
+
+        let stats = task::executor_stats();
+        assert!(hook_calls.get() > 0);
+        assert!(stats.idle_iterations > 0);
+        assert!(stats.iterations >= stats.idle_iterations);
+        assert!(stats.elapsed > Duration::ZERO);
+    }
+
+    #[test]
+    fn polling_main_future_is_not_idle() {
+        let executor = Executor::new();
+
+        let mut polls = 0;
+        executor.block_on(std::future::poll_fn(|cx| {
+            polls += 1;
+            if polls == 10 {
+                return Poll::Ready(());
+            }
+
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }));
+
+        let stats = executor.stats.get();
+        assert_eq!(stats.iterations, 10);
+        assert_eq!(stats.idle_iterations, 0);
+    }
+
+    #[test]
+    fn idle_hook_can_remove_itself() {
+        use crate::{block_on, task, time::sleep};
+
+        let hook_calls = Rc::new(Cell::new(0));
+        task::set_idle_hook({
+            let hook_calls = hook_calls.clone();
+            move || {
+                hook_calls.set(hook_calls.get() + 1);
+                _ = task::take_idle_hook();
+            }
+        });
+
+        block_on(sleep(Duration::from_millis(5)));
+
+        assert_eq!(hook_calls.get(), 1);
+        assert!(task::take_idle_hook().is_none());
+    }
 }
@@ -0,0 +1,310 @@
+//! Utilities for combining futures.
+//!
+//! This module provides macros for running multiple futures concurrently on the current task
+//! without spawning new ones.
+//!
+//! - [`join!`] polls several futures at once and waits for *all* of them to complete, returning a
+//!   tuple of their outputs.
+//! - [`select!`] polls several futures at once and waits for the *first* one to complete, running a
+//!   branch with its output and cancelling the others.
+//!
+//! For waiting on a single future with a deadline, see [`timeout`](crate::time::timeout).
+
+/// Polls multiple futures concurrently, waiting for all of them to complete.
+///
+/// `join!` takes a list of futures and returns a tuple containing the output of each future once
+/// every one of them has completed. All futures are polled on the current task, so no additional
+/// tasks are spawned.
+///
+/// This macro must be used inside of an `async` function or block.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use vexide::prelude::*;
+///
+/// async fn read_left() -> f64 {
+///     sleep(Duration::from_millis(10)).await;
+///     1.0
+/// }
+///
+/// async fn read_right() -> f64 {
+///     sleep(Duration::from_millis(20)).await;
+///     2.0
+/// }
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     // Both reads happen at the same time, so this takes ~20ms rather than ~30ms.
+///     let (left, right) = join!(read_left(), read_right());
+///
+///     assert_eq!(left + right, 3.0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! join {
+    // Expand the normalized list of futures.
+    //
+    // Each future is paired with a list of `_` tokens (one for each future before it), which is
+    // used to pull it back out of the tuple of futures by position.
+    (@ {
+        ( $($count:tt)* )
+        $( ( $($skip:tt)* ) $future:expr, )*
+    }) => {{
+        use $crate::future::__private::{Future, Pin, Poll, maybe_done, poll_fn};
+
+        let mut futures = ( $( maybe_done($future), )* );
+        // SAFETY: Nothing may be moved out of `futures` after this point, since the futures inside
+        // it are pinned below.
+        let futures = &mut futures;
+
+        poll_fn(move |cx| {
+            let mut is_pending = false;
+
+            $(
+                let ( $($skip,)* future, .. ) = &mut *futures;
+                // SAFETY: `future` is never moved (see above).
+                let future = unsafe { Pin::new_unchecked(future) };
+
+                if Future::poll(future, cx).is_pending() {
+                    is_pending = true;
+                }
+            )*
+
+            if is_pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(($({
+                    let ( $($skip,)* future, .. ) = &mut *futures;
+                    // SAFETY: `future` is never moved (see above).
+                    let future = unsafe { Pin::new_unchecked(future) };
+
+                    future.take_output().expect("future should have completed")
+                },)*))
+            }
+        }).await
+    }};
+
+    // Normalize the next future in the list.
+    (@ { ( $($count:tt)* ) $($normalized:tt)* } $future:expr, $($rest:tt)*) => {
+        $crate::join!(@ { ( $($count)* _ ) $($normalized)* ( $($count)* ) $future, } $($rest)*)
+    };
+
+    () => {
+        async {}.await
+    };
+
+    ($($future:expr),+ $(,)?) => {
+        $crate::join!(@ { () } $($future,)+)
+    };
+}
+pub use join;
+
+/// Polls multiple futures concurrently, running a branch for the first one to complete.
+///
+/// Each branch of a `select!` has the form `<pattern> = <future> => <handler>`. All futures are
+/// polled on the current task until one of them completes. Its output is then bound to the branch's
+/// pattern, and the branch's handler is run. The remaining futures are dropped (cancelled) *before*
+/// the handler runs.
+///
+/// `select!` evaluates to the value of the handler that ran. Handlers may also `return`, `break` or
+/// `.await` like any other expression in the enclosing async block.
+///
+/// Branch patterns must be irrefutable.
+///
+/// # Fairness
+///
+/// Futures are polled in the order that their branches are written in, so if more than one future
+/// is ready at the same time, the branch listed first will always be taken.
+///
+/// # Examples
+///
+/// Waiting for either a sensor reading or a timer to finish:
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::prelude::*;
+///
+/// async fn wait_for_object(sensor: &DistanceSensor) -> u32 {
+///     loop {
+///         if let Ok(Some(object)) = sensor.object()
+///             && object.distance < 100
+///         {
+///             return object.distance;
+///         }
+///
+///         sleep(DistanceSensor::UPDATE_INTERVAL).await;
+///     }
+/// }
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let sensor = DistanceSensor::new(peripherals.port_1);
+///
+///     select! {
+///         distance = wait_for_object(&sensor) => println!("Found an object {distance}mm away."),
+///         () = sleep(Duration::from_secs(3)) => println!("Gave up after 3 seconds."),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! select {
+    // Expand the normalized list of branches.
+    //
+    // Each branch is paired with a list of `_` tokens (one for each branch before it), which is
+    // used both to pull its future back out of the tuple of futures by position and to build the
+    // nested `Either` variant used to carry its output out of the poll loop.
+    (@ {
+        ( $($count:tt)* )
+        $( ( $($skip:tt)* ) $bind:tt = $future:tt => $handler:tt, )+
+    }) => {{
+        use $crate::future::__private::{Future, Pin, Poll, poll_fn};
+
+        let output = {
+            let mut futures = ( $( $future, )+ );
+            // SAFETY: Nothing may be moved out of `futures` after this point, since the futures
+            // inside it are pinned below.
+            let futures = &mut futures;
+
+            poll_fn(move |cx| {
+                $(
+                    let ( $($skip,)* future, .. ) = &mut *futures;
+                    // SAFETY: `future` is never moved (see above).
+                    let future = unsafe { Pin::new_unchecked(future) };
+
+                    if let Poll::Ready(output) = Future::poll(future, cx) {
+                        return Poll::Ready($crate::__select_variant!(( $($skip)* ) output));
+                    }
+                )+
+
+                Poll::Pending
+            }).await
+        };
+
+        match output {
+            $( $crate::__select_variant!(( $($skip)* ) $bind) => $handler, )+
+            $crate::__select_variant!(@last ( $($count)* ) never) => {
+                $crate::future::__private::unreachable(never)
+            }
+        }
+    }};
+
+    // Normalize the next branch in the list.
+    (@ { ( $($count:tt)* ) $($normalized:tt)* }
+        $bind:pat = $future:expr => $handler:block, $($rest:tt)*) => {
+        $crate::select!(@ {
+            ( $($count)* _ ) $($normalized)* ( $($count)* ) $bind = $future => $handler,
+        } $($rest)*)
+    };
+    (@ { ( $($count:tt)* ) $($normalized:tt)* }
+        $bind:pat = $future:expr => $handler:block $($rest:tt)*) => {
+        $crate::select!(@ {
+            ( $($count)* _ ) $($normalized)* ( $($count)* ) $bind = $future => $handler,
+        } $($rest)*)
+    };
+    (@ { ( $($count:tt)* ) $($normalized:tt)* }
+        $bind:pat = $future:expr => $handler:expr, $($rest:tt)*) => {
+        $crate::select!(@ {
+            ( $($count)* _ ) $($normalized)* ( $($count)* ) $bind = $future => $handler,
+        } $($rest)*)
+    };
+    (@ { ( $($count:tt)* ) $($normalized:tt)* }
+        $bind:pat = $future:expr => $handler:expr) => {
+        $crate::select!(@ {
+            ( $($count)* _ ) $($normalized)* ( $($count)* ) $bind = $future => $handler,
+        })
+    };
+
+    () => {
+        compile_error!("`select!` requires at least one branch")
+    };
+
+    ($($branches:tt)+) => {
+        $crate::select!(@ { () } $($branches)+)
+    };
+}
+pub use select;
+
+/// Builds the nested [`Either`](futures_util::future::Either) value or pattern for a branch of
+/// [`select!`], given one `_` token for every branch before it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __select_variant {
+    (() $inner:tt) => {
+        $crate::future::__private::Either::Left($inner)
+    };
+    ((_ $($skip:tt)*) $inner:tt) => {
+        $crate::future::__private::Either::Right($crate::__select_variant!(( $($skip)* ) $inner))
+    };
+
+    // The innermost `Right` variant, which can never be constructed.
+    (@last () $inner:tt) => {
+        $inner
+    };
+    (@last (_ $($skip:tt)*) $inner:tt) => {
+        $crate::future::__private::Either::Right(
+            $crate::__select_variant!(@last ( $($skip)* ) $inner)
+        )
+    };
+}
+
+// Items used by the expansions of this module's macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use core::{
+        future::{Future, poll_fn},
+        pin::Pin,
+        task::Poll,
+    };
+
+    pub use futures_util::future::{Either, maybe_done};
+
+    /// Constrains the type of the final, uninhabited variant in a [`select!`] expansion.
+    pub const fn unreachable(never: core::convert::Infallible) -> ! {
+        match never {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use vex_sdk_mock as _;
+
+    use crate::{block_on, time::sleep};
+
+    #[test]
+    fn joins_all_futures() {
+        let output = block_on(async {
+            join!(
+                async {
+                    sleep(Duration::from_millis(2)).await;
+                    1
+                },
+                async { "two" },
+                async {
+                    sleep(Duration::from_millis(1)).await;
+                    3.0
+                },
+            )
+        });
+
+        assert_eq!(output, (1, "two", 3.0));
+    }
+
+    #[test]
+    fn selects_first_completed_future() {
+        let output = block_on(async {
+            select! {
+                () = sleep(Duration::from_secs(10)) => 1,
+                n = async { 2 } => n,
+                () = sleep(Duration::from_millis(1)) => { 3 }
+            }
+        });
+
+        assert_eq!(output, 2);
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    fmt,
+    future::{Future, poll_fn},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::task::{Builder, Task, TaskId};
+
+/// A collection of tasks that are spawned onto the executor and awaited together.
+///
+/// A `JoinSet` owns every task spawned into it. Outputs are returned by [`JoinSet::join_next`] in
+/// the order that the tasks finish, rather than the order that they were spawned in.
+///
+/// When a `JoinSet` is dropped, every task still in it is cancelled. This makes it easy to tie the
+/// lifetime of a group of subtasks to some scope, such as the current competition mode. Each task
+/// in the set is still a separate task with its own [task-local storage](crate::task::task_local).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use vexide::{prelude::*, task::JoinSet};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let mut set = JoinSet::new();
+///
+///     for i in 0..3 {
+///         set.spawn(async move {
+///             sleep(Duration::from_millis(10 * (3 - i))).await;
+///             i
+///         });
+///     }
+///
+///     // Tasks that sleep for less time finish first.
+///     assert_eq!(set.join_next().await, Some(2));
+///     assert_eq!(set.join_next().await, Some(1));
+///     assert_eq!(set.join_next().await, Some(0));
+///     assert_eq!(set.join_next().await, None);
+/// }
+/// ```
+pub struct JoinSet<T> {
+    tasks: Vec<Task<T>>,
+}
+
+impl<T> JoinSet<T> {
+    /// Creates an empty `JoinSet`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { tasks: Vec::new() }
+    }
+
+    /// Returns the number of tasks in the set that haven't been joined yet.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if there are no tasks in the set.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Spawns a new task into the set, returning its ID.
+    ///
+    /// The task is spawned with the default configuration. To configure the task, use
+    /// [`JoinSet::spawn_with`] instead.
+    #[track_caller]
+    pub fn spawn(&mut self, future: impl Future<Output = T> + 'static) -> TaskId {
+        self.spawn_with(Builder::new(), future)
+    }
+
+    /// Spawns a new task into the set using the configuration of the given [`Builder`], returning
+    /// its ID.
+    #[track_caller]
+    pub fn spawn_with(
+        &mut self,
+        builder: Builder,
+        future: impl Future<Output = T> + 'static,
+    ) -> TaskId {
+        self.insert(builder.spawn(future))
+    }
+
+    /// Moves an already spawned task into the set, returning its ID.
+    pub fn insert(&mut self, task: Task<T>) -> TaskId {
+        let id = task.metadata().id();
+        self.tasks.push(task);
+        id
+    }
+
+    /// Waits for any task in the set to finish, returning its output.
+    ///
+    /// Returns [`None`] if the set is empty.
+    ///
+    /// # Cancel Safety
+    ///
+    /// If the returned future is dropped before it completes, no task output is lost, so this
+    /// method is safe to use in [`select!`](crate::future::select).
+    pub async fn join_next(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_join_next(cx)).await
+    }
+
+    /// Polls for any task in the set to finish, returning its output.
+    ///
+    /// This is the poll-based equivalent of [`JoinSet::join_next`]. When this returns
+    /// [`Poll::Pending`], the current task is woken up once any task in the set finishes.
+    pub fn poll_join_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if self.tasks.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        for index in 0..self.tasks.len() {
+            if let Poll::Ready(output) = Pin::new(&mut self.tasks[index]).poll(cx) {
+                // The task has already finished, so removing it won't cancel anything.
+                drop(self.tasks.swap_remove(index));
+                return Poll::Ready(Some(output));
+            }
+        }
+
+        Poll::Pending
+    }
+
+    /// Returns the output of a task in the set that has already finished, without waiting.
+    ///
+    /// Returns [`None`] if no tasks have finished, or if the set is empty.
+    pub fn try_join_next(&mut self) -> Option<T> {
+        let index = self.tasks.iter().position(Task::is_finished)?;
+        let mut task = self.tasks.swap_remove(index);
+
+        // Finished tasks are always ready, so polling them with a no-op waker is fine.
+        match Pin::new(&mut task).poll(&mut Context::from_waker(std::task::Waker::noop())) {
+            Poll::Ready(output) => Some(output),
+            Poll::Pending => unreachable!("finished task should be ready"),
+        }
+    }
+
+    /// Waits for every task in the set to finish, returning their outputs in the order they
+    /// finished.
+    pub async fn join_all(mut self) -> Vec<T> {
+        let mut outputs = Vec::with_capacity(self.len());
+
+        while let Some(output) = self.join_next().await {
+            outputs.push(output);
+        }
+
+        outputs
+    }
+
+    /// Cancels every task in the set, removing them from it.
+    ///
+    /// The cancelled tasks won't be polled again, but may not be destroyed until the next time
+    /// the executor runs. To wait for every task to be destroyed, use [`JoinSet::shutdown`].
+    pub fn abort_all(&mut self) {
+        self.tasks.clear();
+    }
+
+    /// Cancels every task in the set and waits for them to be destroyed.
+    pub async fn shutdown(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.cancel().await;
+        }
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for JoinSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinSet")
+            .field("len", &self.tasks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::{block_on, time::sleep};
+
+    #[test]
+    fn joins_in_completion_order() {
+        let outputs = block_on(async {
+            let mut set = JoinSet::new();
+
+            for i in 0..3u64 {
+                set.spawn(async move {
+                    sleep(Duration::from_millis(3 - i)).await;
+                    i
+                });
+            }
+
+            set.join_all().await
+        });
+
+        assert_eq!(outputs, [2, 1, 0]);
+    }
+
+    #[test]
+    fn cancels_tasks_on_drop() {
+        let polled = Rc::new(Cell::new(0));
+
+        block_on(async {
+            let mut set = JoinSet::new();
+
+            for _ in 0..3 {
+                let polled = polled.clone();
+                set.spawn(async move {
+                    loop {
+                        polled.set(polled.get() + 1);
+                        sleep(Duration::from_millis(1)).await;
+                    }
+                });
+            }
+
+            sleep(Duration::from_millis(5)).await;
+            drop(set);
+
+            let polled_before = polled.get();
+            sleep(Duration::from_millis(5)).await;
+            assert_eq!(polled.get(), polled_before);
+        });
+    }
+}
@@ -6,11 +6,20 @@
 //! futures (such as [`Sleep`](crate::time::Sleep)).
 
 mod executor;
+mod queue;
 mod reactor;
 
+pub mod device;
+pub mod future;
+mod join_set;
 mod local;
+pub mod stream;
+#[cfg(feature = "sync")]
+pub mod sync;
 pub mod task;
 pub mod time;
+#[cfg(panic = "unwind")]
+mod unwind;
 
 use core::future::Future;
 
@@ -18,21 +27,6 @@ pub use task::spawn;
 
 use crate::executor::EXECUTOR;
 
-/// Synchronization primitives for async code.
-///
-/// vexide programs often use async [tasks](crate::task) to run multiple operations concurrently.
-/// These primitives provide methods for tasks to safely communicate with each other and share data.
-/// This is vexide's async equivalent to the [`std::sync` module].
-///
-/// [`std::sync` module]: https://doc.rust-lang.org/stable/std/sync/index.html
-#[cfg(feature = "sync")]
-pub mod sync {
-    pub use async_lock::{
-        Barrier, BarrierWaitResult, Mutex, MutexGuard, OnceCell, RwLock, RwLockReadGuard,
-        RwLockWriteGuard,
-    };
-}
-
 /// Blocks the current task until a return value can be extracted from the provided future.
 ///
 /// Does not poll all futures to completion.
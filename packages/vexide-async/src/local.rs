@@ -100,6 +100,7 @@ macro_rules! task_local {
         $(#[$attr])*
         // publicly reexported in crate::task
         $vis static $name: $crate::task::LocalKey<$type> = {
+            #[allow(clippy::missing_const_for_fn)]
             fn init() -> $type { $init }
             $crate::task::LocalKey::new(init)
         };
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use crate::task::Priority;
+
+/// How many times in a row a ready task may be passed over for higher-priority work before it is
+/// run anyway.
+const STARVATION_LIMIT: u32 = 32;
+
+#[derive(Debug)]
+struct Level<T> {
+    items: VecDeque<T>,
+    /// The number of consecutive pops that skipped this level while it had items ready.
+    skipped: u32,
+}
+
+/// The executor's queue of ready tasks.
+///
+/// Tasks are popped from the highest-priority level that has any ready, in FIFO order within a
+/// level. To prevent a busy high-priority task from starving everything else, a level that has
+/// been skipped [`STARVATION_LIMIT`] times in a row gets to run next regardless of priority.
+#[derive(Debug)]
+pub(crate) struct RunQueue<T> {
+    levels: [Level<T>; Priority::COUNT],
+}
+
+impl<T> RunQueue<T> {
+    pub const fn new() -> Self {
+        Self {
+            levels: [const {
+                Level {
+                    items: VecDeque::new(),
+                    skipped: 0,
+                }
+            }; Priority::COUNT],
+        }
+    }
+
+    pub fn push(&mut self, item: T, priority: Priority) {
+        self.levels[priority as usize].items.push_back(item);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let is_ready = |level: &Level<T>| !level.items.is_empty();
+
+        // Levels are stored lowest-priority first, so search from the back.
+        let starved = self
+            .levels
+            .iter()
+            .rposition(|level| is_ready(level) && level.skipped >= STARVATION_LIMIT);
+        let index = starved.or_else(|| self.levels.iter().rposition(is_ready))?;
+
+        for level in &mut self.levels[..index] {
+            if is_ready(level) {
+                level.skipped += 1;
+            }
+        }
+
+        let level = &mut self.levels[index];
+        level.skipped = 0;
+        level.items.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    #[test]
+    fn pops_highest_priority_first() {
+        let mut queue = RunQueue::new();
+
+        queue.push("low", Priority::Low);
+        queue.push("normal", Priority::Normal);
+        queue.push("high 1", Priority::High);
+        queue.push("high 2", Priority::High);
+
+        assert_eq!(queue.pop(), Some("high 1"));
+        assert_eq!(queue.pop(), Some("high 2"));
+        assert_eq!(queue.pop(), Some("normal"));
+        assert_eq!(queue.pop(), Some("low"));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn low_priority_is_not_starved() {
+        let mut queue = RunQueue::new();
+        queue.push(Priority::Low, Priority::Low);
+
+        for _ in 0..STARVATION_LIMIT {
+            queue.push(Priority::High, Priority::High);
+            assert_eq!(queue.pop(), Some(Priority::High));
+        }
+
+        // The high-priority task is still ready, but the low-priority one has waited long enough.
+        queue.push(Priority::High, Priority::High);
+        assert_eq!(queue.pop(), Some(Priority::Low));
+        assert_eq!(queue.pop(), Some(Priority::High));
+    }
+}
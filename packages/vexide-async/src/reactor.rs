@@ -1,68 +1,170 @@
 use std::{
-    collections::BinaryHeap,
+    collections::BTreeMap,
     task::Waker,
     time::{Duration, Instant},
 };
 
-pub(crate) struct Sleeper {
-    pub deadline: Instant,
-    pub waker: Waker,
-}
+use crate::device::device_timestamp;
 
-impl PartialEq for Sleeper {
-    fn eq(&self, other: &Self) -> bool {
-        other.deadline.eq(&other.deadline)
-    }
-}
-impl PartialOrd for Sleeper {
-    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+/// A handle to a timer registered with the [`Reactor`].
+///
+/// Timers are ordered by deadline first, then by the order that they were registered in, so
+/// that timers sharing a deadline still have unique keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TimerKey {
+    deadline: Instant,
+    id: u64,
 }
 
-impl Eq for Sleeper {}
-impl Ord for Sleeper {
-    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        // NOTE: Sleeper with the earliest deadline (minimum `Instant` ordering) must have the
-        // highest priority in the queue.
-        self.deadline.cmp(&other.deadline).reverse()
-    }
+/// A handle to a device waiter registered with the [`Reactor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct DeviceWaiterKey(u64);
+
+/// A task waiting for a new packet from a Smart device.
+struct DeviceWaiter {
+    index: u32,
+    last_timestamp: u32,
+    waker: Waker,
 }
 
 pub struct Reactor {
-    pub(crate) sleepers: BinaryHeap<Sleeper>,
+    timers: BTreeMap<TimerKey, Waker>,
+    next_timer_id: u64,
+    device_waiters: BTreeMap<DeviceWaiterKey, DeviceWaiter>,
+    next_device_waiter_id: u64,
 }
 
 impl Reactor {
+    /// How often device waiters should be checked on platforms where the executor can yield to the
+    /// OS while idle.
+    const DEVICE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
     pub const fn new() -> Self {
         Self {
-            sleepers: BinaryHeap::new(),
+            timers: BTreeMap::new(),
+            next_timer_id: 0,
+            device_waiters: BTreeMap::new(),
+            next_device_waiter_id: 0,
+        }
+    }
+
+    /// Registers a timer that wakes `waker` once `deadline` is reached.
+    pub fn insert_timer(&mut self, deadline: Instant, waker: Waker) -> TimerKey {
+        let key = TimerKey {
+            deadline,
+            id: self.next_timer_id,
+        };
+        self.next_timer_id += 1;
+
+        self.timers.insert(key, waker);
+        key
+    }
+
+    /// Replaces the waker of a registered timer, unless it would already wake the same task.
+    ///
+    /// Returns `false` if the timer has already fired or been removed.
+    pub fn update_timer(&mut self, key: TimerKey, waker: &Waker) -> bool {
+        let Some(registered) = self.timers.get_mut(&key) else {
+            return false;
+        };
+
+        if !registered.will_wake(waker) {
+            registered.clone_from(waker);
         }
+
+        true
+    }
+
+    /// Deregisters a timer without waking it.
+    pub fn remove_timer(&mut self, key: TimerKey) {
+        self.timers.remove(&key);
     }
 
-    /// Queues any sleepers ready to wake. Returns the time until a sleeper will awaken, or zero if
-    /// one is ready.
+    /// Returns the number of timers that haven't fired yet.
+    #[cfg(test)]
+    pub fn timer_count(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Registers a waiter that wakes `waker` once the Smart device at `index` sends a packet with
+    /// a timestamp other than `last_timestamp`.
+    pub fn insert_device_waiter(
+        &mut self,
+        index: u32,
+        last_timestamp: u32,
+        waker: Waker,
+    ) -> DeviceWaiterKey {
+        let key = DeviceWaiterKey(self.next_device_waiter_id);
+        self.next_device_waiter_id += 1;
+
+        self.device_waiters.insert(
+            key,
+            DeviceWaiter {
+                index,
+                last_timestamp,
+                waker,
+            },
+        );
+        key
+    }
+
+    /// Replaces the waker of a registered device waiter, unless it would already wake the same
+    /// task.
+    ///
+    /// Returns `false` if the waiter has already been woken or removed.
+    pub fn update_device_waiter(&mut self, key: DeviceWaiterKey, waker: &Waker) -> bool {
+        let Some(waiter) = self.device_waiters.get_mut(&key) else {
+            return false;
+        };
+
+        if !waiter.waker.will_wake(waker) {
+            waiter.waker.clone_from(waker);
+        }
+
+        true
+    }
+
+    /// Deregisters a device waiter without waking it.
+    pub fn remove_device_waiter(&mut self, key: DeviceWaiterKey) {
+        self.device_waiters.remove(&key);
+    }
+
+    /// Returns the number of device waiters that haven't been woken yet.
+    #[cfg(test)]
+    pub fn device_waiter_count(&self) -> usize {
+        self.device_waiters.len()
+    }
+
+    /// Wakes any expired timers and device waiters. Returns the time until the reactor should be
+    /// checked again, or zero if something is ready.
     pub fn tick(&mut self) -> Duration {
-        let now = Instant::now();
+        let now = crate::time::now();
         let mut min_ttw = Duration::MAX;
 
-        while let Some(next) = self.sleepers.peek() {
-            let time_to_wake = next.deadline.saturating_duration_since(now);
+        if !self.device_waiters.is_empty() {
+            self.device_waiters.retain(|_, waiter| {
+                let has_update = device_timestamp(waiter.index) != waiter.last_timestamp;
+                if has_update {
+                    waiter.waker.wake_by_ref();
+                }
 
-            if time_to_wake < min_ttw {
-                min_ttw = time_to_wake;
-            }
+                !has_update
+            });
 
-            if time_to_wake.is_zero() {
-                // We want to wake all of the expired sleepers, so don't stop early.
+            // There's no way to know when the next packet will arrive, so keep checking regularly.
+            if !self.device_waiters.is_empty() {
+                min_ttw = Self::DEVICE_POLL_INTERVAL;
+            }
+        }
 
-                let sleeper = self.sleepers.pop().unwrap();
-                sleeper.waker.wake();
+        // Timers are sorted by deadline, so only the expired ones at the front of the map (plus the
+        // first unexpired one) ever need to be looked at.
+        while let Some(entry) = self.timers.first_entry() {
+            if entry.key().deadline <= now {
+                entry.remove().wake();
+                min_ttw = Duration::ZERO;
             } else {
-                // Since we've popped all the expired sleepers, we now just care about how long we
-                // have to wait until the next one. The queue is drained in order,
-                // so we surely have encountered the smallest TTW in the list already.
-
+                min_ttw = min_ttw.min(entry.key().deadline - now);
                 break;
             }
         }
@@ -0,0 +1,569 @@
+//! Utilities for working with asynchronous streams of values.
+//!
+//! A [`Stream`] is the asynchronous version of an [`Iterator`]: it produces a sequence of values,
+//! waiting for each one to become available. Competition status updates, controller inputs, and
+//! sensor readings are all naturally modeled as streams.
+//!
+//! The [`StreamExt`] trait adds adapters to every stream, including time-based ones that are driven
+//! by the runtime's timers:
+//!
+//! - [`map`](StreamExt::map) and [`filter`](StreamExt::filter) transform and select values, like
+//!   their [`Iterator`] counterparts.
+//! - [`throttle`](StreamExt::throttle) spaces values out so that they are yielded no more often
+//!   than some period.
+//! - [`debounce`](StreamExt::debounce) waits for the stream to stop changing before yielding the
+//!   latest value, which is useful for noisy inputs like buttons and limit switches.
+//! - [`sample_every`](StreamExt::sample_every) yields the latest value at a fixed period, dropping
+//!   any values in between.
+//! - [`take_until`](StreamExt::take_until) ends the stream once some future completes, such as a
+//!   [`Sleep`] or a [`CancellationToken`](crate::sync::CancellationToken).
+//!
+//! Only the [`Stream`] trait from `futures-core` (re-exported by `futures-util`) is used, so
+//! streams from other crates work with these adapters and vice versa.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+pub use futures_util::Stream;
+use pin_project::pin_project;
+
+use crate::time::{Interval, MissedTickBehavior, Sleep, interval_at, now, sleep};
+
+/// Converts an iterator into a stream which is always ready to yield the iterator's next value.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::{
+///     prelude::*,
+///     stream::{self, StreamExt},
+/// };
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let mut evens = stream::iter(1..=6).filter(|n| n % 2 == 0);
+///
+///     while let Some(n) = evens.next().await {
+///         println!("{n}");
+///     }
+/// }
+/// ```
+pub fn iter<I: IntoIterator>(iter: I) -> Iter<I::IntoIter> {
+    Iter {
+        iter: iter.into_iter(),
+    }
+}
+
+/// A stream that yields the values of an iterator.
+///
+/// This type is returned by the [`iter`] function.
+#[derive(Debug, Clone)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Iter<I> {
+    iter: I,
+}
+
+impl<I> Unpin for Iter<I> {}
+
+impl<I: Iterator> Stream for Iter<I> {
+    type Item = I::Item;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Extension methods for [`Stream`]s.
+///
+/// This trait is implemented for every type that implements [`Stream`].
+pub trait StreamExt: Stream {
+    /// Waits for the next value in the stream, returning [`None`] once the stream has ended.
+    ///
+    /// # Cancel Safety
+    ///
+    /// If the returned future is dropped before it completes, no values from the stream are lost.
+    fn next(&mut self) -> Next<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Next { stream: self }
+    }
+
+    /// Transforms each value in the stream using a closure.
+    fn map<T, F>(self, f: F) -> Map<Self, F>
+    where
+        F: FnMut(Self::Item) -> T,
+        Self: Sized,
+    {
+        Map { stream: self, f }
+    }
+
+    /// Skips any values in the stream for which `predicate` returns `false`.
+    fn filter<F>(self, predicate: F) -> Filter<Self, F>
+    where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        Filter {
+            stream: self,
+            predicate,
+        }
+    }
+
+    /// Delays values so that at least `period` passes between each value in the stream.
+    ///
+    /// No values are skipped; if the underlying stream produces values faster than `period`, they
+    /// are held back and yielded once enough time has passed.
+    fn throttle(self, period: Duration) -> Throttle<Self>
+    where
+        Self: Sized,
+    {
+        Throttle {
+            stream: self,
+            period,
+            delay: None,
+        }
+    }
+
+    /// Waits until the stream hasn't produced a value for `delay`, then yields the most recent
+    /// value.
+    ///
+    /// Values that are followed by another value within `delay` are skipped. When the underlying
+    /// stream ends, any value that hasn't been yielded yet is yielded immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use vexide::{prelude::*, stream::StreamExt};
+    ///
+    /// #[vexide::main]
+    /// async fn main(_peripherals: Peripherals) {
+    ///     let mut status = vexide::competition::updates()
+    ///         .map(|status| status.mode())
+    ///         .debounce(Duration::from_millis(100));
+    ///
+    ///     while let Some(mode) = status.next().await {
+    ///         println!("Settled on {mode:?}");
+    ///     }
+    /// }
+    /// ```
+    fn debounce(self, delay: Duration) -> Debounce<Self>
+    where
+        Self: Sized,
+    {
+        Debounce {
+            stream: self,
+            delay,
+            sleep: sleep(delay),
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// Yields the most recent value from the stream once every `period`.
+    ///
+    /// Any other values produced during a period are skipped, and nothing is yielded for periods
+    /// where the underlying stream didn't produce a value. When the underlying stream ends, any
+    /// value that hasn't been yielded yet is yielded immediately.
+    fn sample_every(self, period: Duration) -> SampleEvery<Self>
+    where
+        Self: Sized,
+    {
+        let mut interval = interval_at(now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        SampleEvery {
+            stream: self,
+            interval,
+            latest: None,
+            done: false,
+        }
+    }
+
+    /// Ends the stream once `future` completes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use vexide::{prelude::*, stream::StreamExt};
+    ///
+    /// #[vexide::main]
+    /// async fn main(_peripherals: Peripherals) {
+    ///     // Print status updates for the next 15 seconds.
+    ///     let mut updates = vexide::competition::updates().take_until(sleep(Duration::from_secs(15)));
+    ///
+    ///     while let Some(status) = updates.next().await {
+    ///         println!("{status:?}");
+    ///     }
+    /// }
+    /// ```
+    fn take_until<F>(self, future: F) -> TakeUntil<Self, F>
+    where
+        F: Future,
+        Self: Sized,
+    {
+        TakeUntil {
+            stream: self,
+            future: Some(future),
+        }
+    }
+}
+
+impl<S: Stream + ?Sized> StreamExt for S {}
+
+/// A future that waits for the next value in a stream.
+///
+/// This type is returned by the [`StreamExt::next`] method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Next<'a, S: ?Sized> {
+    stream: &'a mut S,
+}
+
+impl<S: Stream + Unpin + ?Sized> Future for Next<'_, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.get_mut().stream).poll_next(cx)
+    }
+}
+
+/// A stream that transforms each value of another stream.
+///
+/// This type is returned by the [`StreamExt::map`] method.
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct Map<S, F> {
+    #[pin]
+    stream: S,
+    f: F,
+}
+
+impl<T, S: Stream, F: FnMut(S::Item) -> T> Stream for Map<S, F> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        this.stream.poll_next(cx).map(|item| item.map(this.f))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for Map<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A stream that skips values of another stream which don't match a predicate.
+///
+/// This type is returned by the [`StreamExt::filter`] method.
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct Filter<S, F> {
+    #[pin]
+    stream: S,
+    predicate: F,
+}
+
+impl<S: Stream, F: FnMut(&S::Item) -> bool> Stream for Filter<S, F> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(item) if !(this.predicate)(&item) => {}
+                item => return Poll::Ready(item),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.stream.size_hint().1)
+    }
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for Filter<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Filter")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A stream that spaces out the values of another stream.
+///
+/// This type is returned by the [`StreamExt::throttle`] method.
+#[pin_project]
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Throttle<S> {
+    #[pin]
+    stream: S,
+    period: Duration,
+    delay: Option<Sleep>,
+}
+
+impl<S: Stream> Stream for Throttle<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        if let Some(delay) = this.delay {
+            ready!(Pin::new(delay).poll(cx));
+            *this.delay = None;
+        }
+
+        let item = ready!(this.stream.poll_next(cx));
+        if item.is_some() {
+            *this.delay = Some(sleep(*this.period));
+        }
+
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// A stream that yields a value of another stream once it stops changing.
+///
+/// This type is returned by the [`StreamExt::debounce`] method.
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct Debounce<S: Stream> {
+    #[pin]
+    stream: S,
+    delay: Duration,
+    sleep: Sleep,
+    pending: Option<S::Item>,
+    done: bool,
+}
+
+impl<S: Stream> Stream for Debounce<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Take every value that's ready, restarting the timer each time.
+        while !*this.done {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    *this.pending = Some(item);
+                    this.sleep.reset(now() + *this.delay);
+                }
+                Poll::Ready(None) => *this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if *this.done {
+            return Poll::Ready(this.pending.take());
+        }
+
+        if this.pending.is_some() {
+            ready!(Pin::new(this.sleep).poll(cx));
+            return Poll::Ready(this.pending.take());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<S: Stream + fmt::Debug> fmt::Debug for Debounce<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debounce")
+            .field("stream", &self.stream)
+            .field("delay", &self.delay)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A stream that yields the latest value of another stream at a fixed period.
+///
+/// This type is returned by the [`StreamExt::sample_every`] method.
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct SampleEvery<S: Stream> {
+    #[pin]
+    stream: S,
+    interval: Interval,
+    latest: Option<S::Item>,
+    done: bool,
+}
+
+impl<S: Stream> Stream for SampleEvery<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => *this.latest = Some(item),
+                Poll::Ready(None) => *this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if *this.done {
+            return Poll::Ready(this.latest.take());
+        }
+
+        loop {
+            ready!(this.interval.poll_tick(cx));
+
+            if let Some(item) = this.latest.take() {
+                return Poll::Ready(Some(item));
+            }
+        }
+    }
+}
+
+impl<S: Stream + fmt::Debug> fmt::Debug for SampleEvery<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleEvery")
+            .field("stream", &self.stream)
+            .field("period", &self.interval.period())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A stream that ends once a future completes.
+///
+/// This type is returned by the [`StreamExt::take_until`] method.
+#[pin_project]
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TakeUntil<S, F> {
+    #[pin]
+    stream: S,
+    #[pin]
+    future: Option<F>,
+}
+
+impl<S: Stream, F: Future> Stream for TakeUntil<S, F> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let Some(future) = this.future.as_mut().as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if future.poll(cx).is_ready() {
+            this.future.set(None);
+            return Poll::Ready(None);
+        }
+
+        this.stream.poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.future.is_some() {
+            (0, self.stream.size_hint().1)
+        } else {
+            (0, Some(0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::block_on;
+
+    /// A stream that counts up once every `period`.
+    #[cfg(feature = "test-util")]
+    fn counter(period: Duration) -> impl Stream<Item = u32> + Unpin {
+        struct Counter(Interval, u32);
+
+        impl Stream for Counter {
+            type Item = u32;
+
+            fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+                let this = self.get_mut();
+                ready!(this.0.poll_tick(cx));
+                this.1 += 1;
+                Poll::Ready(Some(this.1))
+            }
+        }
+
+        Counter(crate::time::interval(period), 0)
+    }
+
+    #[test]
+    fn map_and_filter() {
+        let values = block_on(async {
+            let mut stream = iter(1..=10).map(|n| n * 10).filter(|n| n % 20 == 0);
+            let mut values = Vec::new();
+
+            while let Some(n) = stream.next().await {
+                values.push(n);
+            }
+            values
+        });
+
+        assert_eq!(values, [20, 40, 60, 80, 100]);
+    }
+
+    #[test]
+    fn debounce_yields_latest_value() {
+        let values = block_on(async {
+            let mut stream = iter([1, 2, 3]).debounce(Duration::from_secs(10));
+            [stream.next().await, stream.next().await]
+        });
+
+        // The stream ends before the delay passes, so only the latest value is yielded.
+        assert_eq!(values, [Some(3), None]);
+    }
+
+    #[test]
+    #[cfg(feature = "test-util")]
+    fn timed_adapters() {
+        use crate::time::pause;
+
+        pause();
+        block_on(async {
+            let start = now();
+            let mut throttled = iter(0..3).throttle(Duration::from_millis(100));
+            for i in 0..3 {
+                assert_eq!(throttled.next().await, Some(i));
+            }
+            assert_eq!(now() - start, Duration::from_millis(200));
+
+            let mut sampled = counter(Duration::from_millis(10))
+                .sample_every(Duration::from_millis(35))
+                .take_until(sleep(Duration::from_millis(100)));
+            assert_eq!(sampled.next().await, Some(4));
+            assert_eq!(sampled.next().await, Some(8));
+            assert_eq!(sampled.next().await, None);
+        });
+    }
+}
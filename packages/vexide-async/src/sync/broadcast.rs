@@ -0,0 +1,368 @@
+//! A multi-producer, multi-consumer channel where every receiver sees every value.
+//!
+//! A broadcast channel is created using the [`channel`] function, which returns a [`Sender`] and a
+//! [`Receiver`]. Additional receivers are created with [`Sender::subscribe`], and each one
+//! receives a clone of every value sent *after* it subscribed.
+//!
+//! The channel holds at most a fixed number of values. If a receiver falls too far behind, the
+//! oldest values are overwritten and the receiver's next call to [`Receiver::recv`] returns
+//! [`RecvError::Lagged`] with the number of values it missed. The receiver then continues from the
+//! oldest value still held by the channel.
+//!
+//! The channel is closed once every [`Sender`] has been dropped, at which point
+//! [`Receiver::recv`] returns [`RecvError::Closed`] after the remaining values have been received.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vexide::{prelude::*, sync::broadcast};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Event {
+//!     BallDetected,
+//!     TargetLost,
+//! }
+//!
+//! #[vexide::main]
+//! async fn main(_peripherals: Peripherals) {
+//!     let (tx, mut rx1) = broadcast::channel(16);
+//!     let mut rx2 = tx.subscribe();
+//!
+//!     let logger = spawn(async move {
+//!         while let Ok(event) = rx1.recv().await {
+//!             println!("Logger saw {event:?}");
+//!         }
+//!     });
+//!     let controller = spawn(async move {
+//!         while let Ok(event) = rx2.recv().await {
+//!             println!("Controller saw {event:?}");
+//!         }
+//!     });
+//!
+//!     tx.send(Event::BallDetected).unwrap();
+//!     tx.send(Event::TargetLost).unwrap();
+//!
+//!     drop(tx);
+//!     logger.await;
+//!     controller.await;
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    future::poll_fn,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use super::WakerList;
+
+#[derive(Debug)]
+struct Shared<T> {
+    /// Sent values along with their sequence numbers, oldest first.
+    buffer: VecDeque<(u64, T)>,
+    capacity: usize,
+    /// The sequence number that will be given to the next sent value.
+    next_seq: u64,
+    sender_count: usize,
+    receiver_count: usize,
+    receiver_wakers: WakerList,
+}
+
+/// Creates a broadcast channel that holds at most `capacity` values, returning the sending half and
+/// a first receiving half.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+#[must_use]
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel capacity must be non-zero");
+
+    let shared = Rc::new(RefCell::new(Shared {
+        buffer: VecDeque::with_capacity(capacity),
+        capacity,
+        next_seq: 0,
+        sender_count: 1,
+        receiver_count: 1,
+        receiver_wakers: WakerList::new(),
+    }));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared, next: 0 },
+    )
+}
+
+/// The sending half of a [broadcast channel](self).
+///
+/// This is created by the [`channel`] function, and may be cloned to send values from multiple
+/// tasks.
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T: Clone> Sender<T> {
+    /// Sends a value to every active [`Receiver`], returning the number of receivers it was sent
+    /// to.
+    ///
+    /// This never waits. If the channel is full, the oldest value is overwritten, and receivers
+    /// that had not yet received it will see a [`RecvError::Lagged`] error.
+    ///
+    /// # Errors
+    ///
+    /// If there are no active receivers, the value is returned back in a [`SendError`].
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let mut shared = self.shared.borrow_mut();
+
+        if shared.receiver_count == 0 {
+            return Err(SendError(value));
+        }
+
+        let seq = shared.next_seq;
+        shared.next_seq += 1;
+
+        if shared.buffer.len() == shared.capacity {
+            shared.buffer.pop_front();
+        }
+        shared.buffer.push_back((seq, value));
+        shared.receiver_wakers.wake_all();
+
+        Ok(shared.receiver_count)
+    }
+
+    /// Creates a new [`Receiver`] that will receive every value sent after this call.
+    #[must_use]
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut shared = self.shared.borrow_mut();
+        shared.receiver_count += 1;
+
+        Receiver {
+            shared: self.shared.clone(),
+            next: shared.next_seq,
+        }
+    }
+
+    /// Returns the number of active receivers.
+    #[must_use]
+    pub fn receiver_count(&self) -> usize {
+        self.shared.borrow().receiver_count
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().sender_count += 1;
+
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.sender_count -= 1;
+
+        // Let waiting receivers know that the channel was closed.
+        if shared.sender_count == 0 {
+            shared.receiver_wakers.wake_all();
+        }
+    }
+}
+
+/// The receiving half of a [broadcast channel](self).
+///
+/// This is created by the [`channel`] function or by [`Sender::subscribe`].
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+    /// The sequence number of the next value this receiver expects.
+    next: u64,
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receives the next value from the channel, waiting until one is sent if this receiver has
+    /// already seen every value.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`RecvError::Lagged`] if values were overwritten before this receiver could
+    ///   receive them. The next call will return the oldest value still in the channel.
+    /// - Returns [`RecvError::Closed`] if this receiver has seen every value and every [`Sender`]
+    ///   has been dropped.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Lagged(missed)) => Poll::Ready(Err(RecvError::Lagged(missed))),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+            Err(TryRecvError::Empty) => {
+                self.shared
+                    .borrow_mut()
+                    .receiver_wakers
+                    .register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Attempts to receive the next value from the channel without waiting.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`TryRecvError::Empty`] if this receiver has already seen every value.
+    /// - Returns [`TryRecvError::Lagged`] if values were overwritten before this receiver could
+    ///   receive them.
+    /// - Returns [`TryRecvError::Closed`] if this receiver has seen every value and every
+    ///   [`Sender`] has been dropped.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let shared = self.shared.borrow();
+
+        let Some(&(oldest, _)) = shared.buffer.front() else {
+            return Err(if shared.sender_count == 0 {
+                TryRecvError::Closed
+            } else {
+                TryRecvError::Empty
+            });
+        };
+
+        if self.next < oldest {
+            let missed = oldest - self.next;
+            self.next = oldest;
+            return Err(TryRecvError::Lagged(missed));
+        }
+
+        if self.next == shared.next_seq {
+            return Err(if shared.sender_count == 0 {
+                TryRecvError::Closed
+            } else {
+                TryRecvError::Empty
+            });
+        }
+
+        // Sequence numbers in the buffer are contiguous, so the offset from the oldest one is the
+        // value's index.
+        let index = (self.next - oldest) as usize;
+        self.next += 1;
+
+        Ok(shared.buffer[index].1.clone())
+    }
+
+    /// Creates a new [`Receiver`] that will receive every value sent after this call.
+    ///
+    /// Unlike cloning, the new receiver does not see any values that this receiver hasn't
+    /// received yet.
+    #[must_use]
+    pub fn resubscribe(&self) -> Self {
+        let mut shared = self.shared.borrow_mut();
+        shared.receiver_count += 1;
+
+        Self {
+            shared: self.shared.clone(),
+            next: shared.next_seq,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.borrow_mut().receiver_count -= 1;
+    }
+}
+
+/// An error returned by [`Sender::send`] when there are no active receivers.
+///
+/// The value that failed to send is contained in this error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T: fmt::Debug> Error for SendError<T> {}
+
+/// An error returned by [`Receiver::recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// Every [`Sender`] has been dropped and there are no more values to receive.
+    Closed,
+
+    /// The receiver fell behind, and the contained number of values were overwritten before it
+    /// could receive them.
+    Lagged(u64),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("channel closed"),
+            Self::Lagged(missed) => write!(f, "receiver lagged behind by {missed} values"),
+        }
+    }
+}
+
+impl Error for RecvError {}
+
+/// An error returned by [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The receiver has already seen every value in the channel.
+    Empty,
+
+    /// Every [`Sender`] has been dropped and there are no more values to receive.
+    Closed,
+
+    /// The receiver fell behind, and the contained number of values were overwritten before it
+    /// could receive them.
+    Lagged(u64),
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("channel empty"),
+            Self::Closed => f.write_str("channel closed"),
+            Self::Lagged(missed) => write!(f, "receiver lagged behind by {missed} values"),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    #[test]
+    fn lagging_receiver_skips_overwritten_values() {
+        let (tx, mut rx) = channel(2);
+
+        for i in 0..5 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Lagged(3)));
+        assert_eq!(rx.try_recv(), Ok(3));
+        assert_eq!(rx.try_recv(), Ok(4));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Closed));
+    }
+}
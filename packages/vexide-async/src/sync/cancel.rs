@@ -0,0 +1,275 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::{Future, poll_fn},
+    pin::pin,
+    rc::{Rc, Weak},
+    task::Poll,
+};
+
+use super::WakerList;
+use crate::task::task_local;
+
+task_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = RefCell::new(None);
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    cancelled: Cell<bool>,
+    wakers: RefCell<WakerList>,
+    children: RefCell<Vec<Weak<Node>>>,
+}
+
+impl Node {
+    fn cancel(&self) {
+        if self.cancelled.replace(true) {
+            return;
+        }
+
+        self.wakers.borrow_mut().wake_all();
+
+        for child in self.children.take() {
+            if let Some(child) = child.upgrade() {
+                child.cancel();
+            }
+        }
+    }
+}
+
+/// A token for signalling that some work should stop.
+///
+/// Dropping a task's [`Task`](crate::task::Task) handle cancels it immediately, at whichever
+/// `await` point it happens to be stopped at. Sometimes, work needs to be stopped at a well-defined
+/// point instead, such as an autonomous routine that should stop its motors before exiting. A
+/// `CancellationToken` allows the work to check whether it has been cancelled and clean up after
+/// itself.
+///
+/// Cloned tokens refer to the same underlying token, so cancelling one clone cancels all of them.
+/// [Child tokens](CancellationToken::child_token) are cancelled along with their parent, but can
+/// also be cancelled on their own without affecting the parent.
+///
+/// # Task-Local Tokens
+///
+/// A token can be [installed](CancellationToken::install) into the current task's
+/// [task-local storage](crate::task::task_local), after which it can be retrieved anywhere in that
+/// task with [`current_token`]. This avoids needing to pass a token through every helper function
+/// in a routine.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{
+///     prelude::*,
+///     smart::motor::BrakeMode,
+///     sync::{CancellationToken, current_token},
+/// };
+///
+/// async fn drive_forward(motor: &mut Motor) {
+///     let token = current_token();
+///
+///     while !token.is_cancelled() {
+///         _ = motor.set_voltage(6.0);
+///         sleep(Duration::from_millis(10)).await;
+///     }
+///
+///     // Stop cleanly instead of leaving the motor running.
+///     _ = motor.brake(BrakeMode::Brake);
+/// }
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let mut motor = Motor::new(peripherals.port_1, Gearset::Green, Direction::Forward);
+///     let token = CancellationToken::new();
+///
+///     let routine = spawn({
+///         let token = token.clone();
+///         async move {
+///             token.install();
+///             drive_forward(&mut motor).await;
+///         }
+///     });
+///
+///     sleep(Duration::from_secs(1)).await;
+///     token.cancel();
+///     routine.await;
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    node: Rc<Node>,
+}
+
+impl CancellationToken {
+    /// Creates a new token that hasn't been cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a child token, which is cancelled when this token is cancelled.
+    ///
+    /// Cancelling the child token does not cancel this token. If this token has already been
+    /// cancelled, the child token starts out cancelled.
+    #[must_use]
+    pub fn child_token(&self) -> Self {
+        let child = Self::new();
+
+        if self.is_cancelled() {
+            child.node.cancelled.set(true);
+        } else {
+            let mut children = self.node.children.borrow_mut();
+            children.retain(|child| child.strong_count() > 0);
+            children.push(Rc::downgrade(&child.node));
+        }
+
+        child
+    }
+
+    /// Cancels this token and all of its children, waking any tasks waiting on
+    /// [`CancellationToken::cancelled`].
+    ///
+    /// Cancelling a token more than once does nothing.
+    pub fn cancel(&self) {
+        self.node.cancel();
+    }
+
+    /// Returns `true` if this token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.node.cancelled.get()
+    }
+
+    /// Waits until this token is cancelled.
+    ///
+    /// This completes immediately if the token has already been cancelled.
+    pub async fn cancelled(&self) {
+        poll_fn(|cx| {
+            if self.is_cancelled() {
+                Poll::Ready(())
+            } else {
+                self.node.wakers.borrow_mut().register(cx.waker());
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+
+    /// Runs a future until it completes or this token is cancelled, whichever happens first.
+    ///
+    /// Returns [`None`] if the token was cancelled before the future completed, in which case the
+    /// future is dropped.
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = pin!(future);
+        let mut cancelled = pin!(self.cancelled());
+
+        poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+
+            future.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+
+    /// Installs this token as the current task's token, returning the previously installed token.
+    ///
+    /// Once installed, the token can be retrieved from anywhere in the current task using
+    /// [`current_token`]. Other tasks, including ones spawned by the current task, are not
+    /// affected.
+    #[allow(clippy::must_use_candidate)]
+    pub fn install(&self) -> Option<Self> {
+        CURRENT_TOKEN.replace(Some(self.clone()))
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Returns the cancellation token [installed](CancellationToken::install) in the current task.
+///
+/// If no token has been installed yet, a new one is created and installed, so the returned token
+/// will only be cancelled if some other code gets a hold of it through this function.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::{
+///     prelude::*,
+///     sync::{CancellationToken, current_token},
+/// };
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let token = CancellationToken::new();
+///     token.install();
+///
+///     token.cancel();
+///     assert!(current_token().is_cancelled());
+/// }
+/// ```
+#[must_use]
+pub fn current_token() -> CancellationToken {
+    CURRENT_TOKEN.with_borrow_mut(|token| token.get_or_insert_with(CancellationToken::new).clone())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::{block_on, spawn, time::sleep};
+
+    #[test]
+    fn cancels_children() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let grandchild = child.child_token();
+
+        child.cancel();
+        assert!(!parent.is_cancelled());
+        assert!(grandchild.is_cancelled());
+
+        let sibling = parent.child_token();
+        parent.cancel();
+        assert!(sibling.is_cancelled());
+        assert!(parent.child_token().is_cancelled());
+    }
+
+    #[test]
+    fn stops_task_at_cancellation_point() {
+        let token = CancellationToken::new();
+
+        let iterations = block_on(async {
+            let task = spawn({
+                let token = token.clone();
+                async move {
+                    token.install();
+
+                    let mut iterations = 0;
+                    while !current_token().is_cancelled() {
+                        iterations += 1;
+                        sleep(Duration::from_millis(1)).await;
+                    }
+                    iterations
+                }
+            });
+
+            sleep(Duration::from_millis(5)).await;
+            token.cancel();
+            task.await
+        });
+
+        assert!(iterations > 0);
+    }
+}
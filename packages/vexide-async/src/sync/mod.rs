@@ -0,0 +1,69 @@
+//! Synchronization primitives for async code.
+//!
+//! vexide programs often use async [tasks](crate::task) to run multiple operations concurrently.
+//! These primitives provide methods for tasks to safely communicate with each other and share data.
+//! This is vexide's async equivalent to the [`std::sync` module].
+//!
+//! [`std::sync` module]: https://doc.rust-lang.org/stable/std/sync/index.html
+//!
+//! # Message Passing
+//!
+//! In addition to locks, this module provides several kinds of channels for sending values
+//! between tasks:
+//!
+//! - [`oneshot`] sends a single value from one task to another, such as the result of a
+//!   computation.
+//! - [`mpsc`] sends a queue of values from any number of tasks to a single receiving task, such as
+//!   commands for a subsystem.
+//! - [`broadcast`] sends every value to every one of many receiving tasks, such as events.
+//! - [`watch`] shares only the most recent value with many receiving tasks, such as the robot's
+//!   current position.
+//!
+//! These channels are designed for vexide's single-threaded executor, so they are cheap to use but
+//! cannot be sent across threads.
+//!
+//! # Cancellation
+//!
+//! A [`CancellationToken`] signals to a task that it should stop what it's doing at the next
+//! convenient point, rather than being dropped in the middle of its work.
+
+use std::task::Waker;
+
+pub use async_lock::{
+    Barrier, BarrierWaitResult, Mutex, MutexGuard, OnceCell, RwLock, RwLockReadGuard,
+    RwLockWriteGuard,
+};
+pub use cancel::{CancellationToken, current_token};
+
+pub mod broadcast;
+mod cancel;
+pub mod mpsc;
+pub mod oneshot;
+pub mod watch;
+
+/// A list of wakers belonging to tasks waiting on a channel.
+#[derive(Debug, Default)]
+struct WakerList {
+    wakers: Vec<Waker>,
+}
+
+impl WakerList {
+    const fn new() -> Self {
+        Self { wakers: Vec::new() }
+    }
+
+    /// Registers a waker to be woken by the next call to [`WakerList::wake_all`], if one that would
+    /// wake the same task isn't registered already.
+    fn register(&mut self, waker: &Waker) {
+        if !self.wakers.iter().any(|w| w.will_wake(waker)) {
+            self.wakers.push(waker.clone());
+        }
+    }
+
+    /// Wakes and removes every registered waker.
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! A multi-producer, single-consumer queue for sending values between tasks.
+//!
+//! An mpsc channel is created using the [`channel`] or [`unbounded_channel`] functions, which
+//! return a [`Sender`] and a [`Receiver`]. The [`Sender`] can be cloned to send values from many
+//! tasks, and values are received in the order they were sent.
+//!
+//! A *bounded* channel created by [`channel`] holds at most a fixed number of values. Once it is
+//! full, [`Sender::send`] waits until the receiver makes room, which keeps a fast producer from
+//! using up all of the brain's memory. An *unbounded* channel created by [`unbounded_channel`]
+//! never waits, but may grow without limit.
+//!
+//! The channel is closed once every [`Sender`] has been dropped, at which point
+//! [`Receiver::recv`] returns [`None`] after the remaining values have been received.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vexide::{prelude::*, sync::mpsc};
+//!
+//! enum IntakeCommand {
+//!     Intake,
+//!     Outtake,
+//!     Stop,
+//! }
+//!
+//! #[vexide::main]
+//! async fn main(_peripherals: Peripherals) {
+//!     let (tx, mut rx) = mpsc::channel(8);
+//!
+//!     let intake = spawn(async move {
+//!         while let Some(command) = rx.recv().await {
+//!             match command {
+//!                 IntakeCommand::Intake => println!("Intaking"),
+//!                 IntakeCommand::Outtake => println!("Outtaking"),
+//!                 IntakeCommand::Stop => println!("Stopping"),
+//!             }
+//!         }
+//!     });
+//!
+//!     tx.send(IntakeCommand::Intake).await.unwrap();
+//!     tx.send(IntakeCommand::Stop).await.unwrap();
+//!
+//!     // Dropping the sender closes the channel, allowing the intake task to finish.
+//!     drop(tx);
+//!     intake.await;
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    future::poll_fn,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use futures_util::Stream;
+
+use super::WakerList;
+
+#[derive(Debug)]
+struct Shared<T> {
+    queue: VecDeque<T>,
+    capacity: Option<usize>,
+    sender_count: usize,
+    receiver_dropped: bool,
+    receiver_waker: Option<Waker>,
+    sender_wakers: WakerList,
+}
+
+impl<T> Shared<T> {
+    fn is_full(&self) -> bool {
+        self.capacity
+            .is_some_and(|capacity| self.queue.len() >= capacity)
+    }
+}
+
+fn new_channel<T>(capacity: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        queue: VecDeque::new(),
+        capacity,
+        sender_count: 1,
+        receiver_dropped: false,
+        receiver_waker: None,
+        sender_wakers: WakerList::new(),
+    }));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Creates a bounded mpsc channel that holds at most `capacity` values, returning the sending and
+/// receiving halves.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+#[must_use]
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "mpsc channel capacity must be non-zero");
+    new_channel(Some(capacity))
+}
+
+/// Creates an unbounded mpsc channel, returning the sending and receiving halves.
+///
+/// Sending to an unbounded channel never waits, so there is no limit on how many values the
+/// channel may hold.
+///
+/// See the [module-level documentation](self) for more information.
+#[must_use]
+pub fn unbounded_channel<T>() -> (Sender<T>, Receiver<T>) {
+    new_channel(None)
+}
+
+/// The sending half of an [mpsc channel](self).
+///
+/// This is created by the [`channel`] and [`unbounded_channel`] functions, and may be cloned to
+/// send values from multiple tasks.
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a value to the channel, waiting until there is room for it if the channel is full.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Receiver`] has been dropped, the value is returned back in a [`SendError`].
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut value = Some(value);
+
+        poll_fn(|cx| {
+            match self.try_send(value.take().unwrap()) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(TrySendError::Closed(value)) => Poll::Ready(Err(SendError(value))),
+                Err(TrySendError::Full(returned)) => {
+                    // Hold onto the value until the receiver makes room for it.
+                    value = Some(returned);
+                    self.shared.borrow_mut().sender_wakers.register(cx.waker());
+
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Attempts to send a value to the channel without waiting.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`TrySendError::Full`] if the channel is bounded and has no room for the value.
+    /// - Returns [`TrySendError::Closed`] if the [`Receiver`] has been dropped.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut shared = self.shared.borrow_mut();
+
+        if shared.receiver_dropped {
+            return Err(TrySendError::Closed(value));
+        }
+        if shared.is_full() {
+            return Err(TrySendError::Full(value));
+        }
+
+        shared.queue.push_back(value);
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the [`Receiver`] has been dropped or closed, meaning that sending a value
+    /// would fail.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().receiver_dropped
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().sender_count += 1;
+
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.sender_count -= 1;
+
+        // Let the receiver know that the channel was closed.
+        if shared.sender_count == 0
+            && let Some(waker) = shared.receiver_waker.take()
+        {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving half of an [mpsc channel](self).
+///
+/// This is created by the [`channel`] and [`unbounded_channel`] functions.
+///
+/// The receiver also implements [`Stream`], yielding values until the channel is closed.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receives the next value from the channel, waiting until one is sent if the channel is
+    /// empty.
+    ///
+    /// Returns [`None`] once the channel is empty and every [`Sender`] has been dropped.
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls to receive the next value from the channel.
+    ///
+    /// This is the poll-based equivalent of [`Receiver::recv`].
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => {
+                self.shared.borrow_mut().receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Attempts to receive the next value from the channel without waiting.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`TryRecvError::Empty`] if there are no values in the channel.
+    /// - Returns [`TryRecvError::Closed`] if there are no values in the channel and every
+    ///   [`Sender`] has been dropped.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut shared = self.shared.borrow_mut();
+
+        match shared.queue.pop_front() {
+            Some(value) => {
+                // We just made room in the channel, so waiting senders can try again.
+                shared.sender_wakers.wake_all();
+                Ok(value)
+            }
+            None if shared.sender_count == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Closes the channel, preventing any more values from being sent.
+    ///
+    /// Values that were already sent may still be received.
+    pub fn close(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.receiver_dropped = true;
+        shared.sender_wakers.wake_all();
+    }
+
+    /// Returns the number of values currently waiting in the channel.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shared.borrow().queue.len()
+    }
+
+    /// Returns `true` if there are no values currently waiting in the channel.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shared.borrow().queue.is_empty()
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_recv(cx)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// An error returned by [`Sender::send`] when the [`Receiver`] has been dropped.
+///
+/// The value that failed to send is contained in this error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T: fmt::Debug> Error for SendError<T> {}
+
+/// An error returned by [`Sender::try_send`].
+///
+/// The value that failed to send is contained in this error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The channel has no room for the value.
+    Full(T),
+
+    /// The [`Receiver`] has been dropped.
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Consumes the error, returning the value that failed to send.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Full(_) => "channel full",
+            Self::Closed(_) => "channel closed",
+        })
+    }
+}
+
+impl<T: fmt::Debug> Error for TrySendError<T> {}
+
+/// An error returned by [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// There are no values waiting in the channel.
+    Empty,
+
+    /// There are no values waiting in the channel, and every [`Sender`] has been dropped.
+    Closed,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Empty => "channel empty",
+            Self::Closed => "channel closed",
+        })
+    }
+}
+
+impl Error for TryRecvError {}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::{block_on, spawn};
+
+    #[test]
+    fn waits_for_capacity() {
+        let received = block_on(async {
+            let (tx, mut rx) = channel(1);
+
+            let producer = spawn(async move {
+                for i in 0..4 {
+                    tx.send(i).await.unwrap();
+                }
+            });
+
+            let mut received = Vec::new();
+            while let Some(value) = rx.recv().await {
+                assert!(rx.len() <= 1);
+                received.push(value);
+            }
+
+            producer.await;
+            received
+        });
+
+        assert_eq!(received, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn send_fails_when_closed() {
+        let (tx, rx) = unbounded_channel();
+        drop(rx);
+
+        assert_eq!(tx.try_send(1), Err(TrySendError::Closed(1)));
+    }
+}
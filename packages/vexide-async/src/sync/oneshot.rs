@@ -0,0 +1,258 @@
+//! A channel for sending a single value between tasks.
+//!
+//! A oneshot channel is created using the [`channel`] function, which returns a [`Sender`] and a
+//! [`Receiver`]. The sender may be used to send exactly one value, which the receiver can then
+//! `await`.
+//!
+//! If the [`Sender`] is dropped without sending a value, the receiver completes with a
+//! [`RecvError`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vexide::{prelude::*, sync::oneshot};
+//!
+//! #[vexide::main]
+//! async fn main(_peripherals: Peripherals) {
+//!     let (tx, rx) = oneshot::channel();
+//!
+//!     spawn(async move {
+//!         // Do some expensive work in the background...
+//!         _ = tx.send(42);
+//!     })
+//!     .detach();
+//!
+//!     assert_eq!(rx.await, Ok(42));
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Debug)]
+struct Shared<T> {
+    value: Option<T>,
+    receiver_waker: Option<Waker>,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+}
+
+/// Creates a new oneshot channel, returning the sending and receiving halves.
+///
+/// See the [module-level documentation](self) for more information.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        value: None,
+        receiver_waker: None,
+        sender_dropped: false,
+        receiver_dropped: false,
+    }));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// The sending half of a [oneshot channel](self).
+///
+/// This is created by the [`channel`] function.
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a value to the receiving half of the channel, consuming the sender.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Receiver`] has already been dropped, the value is returned back in [`Err`].
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut shared = self.shared.borrow_mut();
+
+        if shared.receiver_dropped {
+            return Err(value);
+        }
+
+        shared.value = Some(value);
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the [`Receiver`] has been dropped, meaning that sending a value would
+    /// fail.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().receiver_dropped
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.sender_dropped = true;
+
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving half of a [oneshot channel](self).
+///
+/// This is created by the [`channel`] function. The receiver is a future which completes with the
+/// sent value, or with a [`RecvError`] if the [`Sender`] was dropped without sending one.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Attempts to receive the value without waiting for it.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`TryRecvError::Empty`] if no value has been sent yet.
+    /// - Returns [`TryRecvError::Closed`] if the [`Sender`] was dropped without sending a value, or
+    ///   if the value was already received.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut shared = self.shared.borrow_mut();
+
+        match shared.value.take() {
+            Some(value) => Ok(value),
+            None if shared.sender_dropped => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        match this.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => {
+                this.shared.borrow_mut().receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.borrow_mut().receiver_dropped = true;
+    }
+}
+
+/// An error returned when awaiting a [`Receiver`] whose [`Sender`] was dropped without sending a
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl Error for RecvError {}
+
+/// An error returned by [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No value has been sent yet.
+    Empty,
+
+    /// The [`Sender`] was dropped without sending a value, or the value was already received.
+    Closed,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Empty => "channel empty",
+            Self::Closed => "channel closed",
+        })
+    }
+}
+
+impl Error for TryRecvError {}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::{block_on, spawn};
+
+    #[test]
+    fn receiver_gets_sent_value() {
+        let (tx, rx) = channel();
+
+        let received = block_on(async move {
+            spawn(async move {
+                tx.send(42).unwrap();
+            })
+            .detach();
+
+            rx.await
+        });
+
+        assert_eq!(received, Ok(42));
+    }
+
+    #[test]
+    fn sender_dropped_closes_channel() {
+        let (tx, mut rx) = channel::<i32>();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        let received = block_on(async move {
+            spawn(async move {
+                drop(tx);
+            })
+            .detach();
+
+            rx.await
+        });
+
+        assert_eq!(received, Err(RecvError));
+    }
+
+    #[test]
+    fn value_is_only_received_once() {
+        let (tx, mut rx) = channel();
+
+        tx.send(1).unwrap();
+        assert_eq!(rx.try_recv(), Ok(1));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Closed));
+    }
+
+    #[test]
+    fn send_fails_after_receiver_dropped() {
+        let (tx, rx) = channel();
+        assert!(!tx.is_closed());
+
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(1), Err(1));
+    }
+}
@@ -0,0 +1,318 @@
+//! A single-producer, multi-consumer channel that only holds the most recent value.
+//!
+//! A watch channel is created using the [`channel`] function, which takes an initial value and
+//! returns a [`Sender`] and a [`Receiver`]. Receivers may be cloned, and each one can
+//! [borrow](Receiver::borrow) the current value at any time or wait for it to
+//! [change](Receiver::changed).
+//!
+//! Unlike other channels, intermediate values are not queued. A receiver that waits for a change
+//! only ever observes the latest value, which makes watch channels a good fit for state that is
+//! updated continuously, such as the robot's position from an odometry task.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use vexide::{prelude::*, sync::watch};
+//!
+//! #[derive(Clone, Copy, Debug, Default)]
+//! struct Pose {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! #[vexide::main]
+//! async fn main(_peripherals: Peripherals) {
+//!     let (tx, mut rx) = watch::channel(Pose::default());
+//!
+//!     spawn(async move {
+//!         loop {
+//!             tx.send_modify(|pose| pose.x += 1.0);
+//!             sleep(Duration::from_millis(10)).await;
+//!         }
+//!     })
+//!     .detach();
+//!
+//!     while rx.changed().await.is_ok() {
+//!         let pose = *rx.borrow_and_update();
+//!         println!("Robot is at ({}, {})", pose.x, pose.y);
+//!     }
+//! }
+//! ```
+
+use std::{
+    cell::{Ref, RefCell},
+    error::Error,
+    fmt,
+    future::poll_fn,
+    mem,
+    rc::Rc,
+    task::Poll,
+};
+
+use super::WakerList;
+
+#[derive(Debug)]
+struct Shared<T> {
+    value: T,
+    /// Incremented every time the value is updated.
+    version: u64,
+    receiver_count: usize,
+    sender_dropped: bool,
+    receiver_wakers: WakerList,
+}
+
+/// Creates a new watch channel holding `init`, returning the sending and receiving halves.
+///
+/// See the [module-level documentation](self) for more information.
+#[must_use]
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        value: init,
+        version: 0,
+        receiver_count: 1,
+        sender_dropped: false,
+        receiver_wakers: WakerList::new(),
+    }));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver {
+            shared,
+            seen_version: 0,
+        },
+    )
+}
+
+/// The sending half of a [watch channel](self).
+///
+/// This is created by the [`channel`] function.
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Replaces the value in the channel and notifies every [`Receiver`].
+    ///
+    /// # Errors
+    ///
+    /// If there are no active receivers, the value is not stored and is returned back in a
+    /// [`SendError`].
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.receiver_count() == 0 {
+            return Err(SendError(value));
+        }
+
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// Replaces the value in the channel, notifies every [`Receiver`], and returns the previous
+    /// value.
+    ///
+    /// Unlike [`Sender::send`], this stores the value even if there are no active receivers.
+    pub fn send_replace(&self, value: T) -> T {
+        let mut old = Some(value);
+        self.send_modify(|current| old = Some(mem::replace(current, old.take().unwrap())));
+        old.unwrap()
+    }
+
+    /// Modifies the value in the channel in place and notifies every [`Receiver`].
+    ///
+    /// The value is stored even if there are no active receivers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modify` tries to borrow the value through this channel.
+    pub fn send_modify(&self, modify: impl FnOnce(&mut T)) {
+        let mut shared = self.shared.borrow_mut();
+
+        modify(&mut shared.value);
+        shared.version += 1;
+        shared.receiver_wakers.wake_all();
+    }
+
+    /// Returns a reference to the current value in the channel.
+    ///
+    /// The channel can't be updated while the returned reference is held.
+    #[must_use]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref::map(self.shared.borrow(), |shared| &shared.value)
+    }
+
+    /// Creates a new [`Receiver`] that has already seen the current value.
+    #[must_use]
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut shared = self.shared.borrow_mut();
+        shared.receiver_count += 1;
+
+        Receiver {
+            shared: self.shared.clone(),
+            seen_version: shared.version,
+        }
+    }
+
+    /// Returns the number of active receivers.
+    #[must_use]
+    pub fn receiver_count(&self) -> usize {
+        self.shared.borrow().receiver_count
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.sender_dropped = true;
+        shared.receiver_wakers.wake_all();
+    }
+}
+
+/// The receiving half of a [watch channel](self).
+///
+/// This is created by the [`channel`] function or by [`Sender::subscribe`], and may be cloned.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+    /// The version of the value that this receiver last saw.
+    seen_version: u64,
+}
+
+impl<T> Receiver<T> {
+    /// Returns a reference to the current value in the channel without marking it as seen.
+    ///
+    /// The channel can't be updated while the returned reference is held.
+    #[must_use]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref::map(self.shared.borrow(), |shared| &shared.value)
+    }
+
+    /// Returns a reference to the current value in the channel and marks it as seen.
+    ///
+    /// The channel can't be updated while the returned reference is held.
+    #[must_use]
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let shared = self.shared.borrow();
+        self.seen_version = shared.version;
+
+        Ref::map(shared, |shared| &shared.value)
+    }
+
+    /// Returns `true` if the value has changed since it was last seen by this receiver.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RecvError`] if the [`Sender`] has been dropped.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        let shared = self.shared.borrow();
+
+        if shared.sender_dropped {
+            return Err(RecvError);
+        }
+
+        Ok(shared.version != self.seen_version)
+    }
+
+    /// Waits for the value to change from the one last seen by this receiver, then marks the new
+    /// value as seen.
+    ///
+    /// This completes immediately if the value has already changed since it was last seen. Use
+    /// [`Receiver::borrow`] to access the new value afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RecvError`] if the [`Sender`] was dropped before the value changed.
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        poll_fn(|cx| {
+            let mut shared = self.shared.borrow_mut();
+
+            if shared.version != self.seen_version {
+                self.seen_version = shared.version;
+                Poll::Ready(Ok(()))
+            } else if shared.sender_dropped {
+                Poll::Ready(Err(RecvError))
+            } else {
+                shared.receiver_wakers.register(cx.waker());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().receiver_count += 1;
+
+        Self {
+            shared: self.shared.clone(),
+            seen_version: self.seen_version,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.borrow_mut().receiver_count -= 1;
+    }
+}
+
+/// An error returned by [`Sender::send`] when there are no active receivers.
+///
+/// The value that failed to send is contained in this error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T: fmt::Debug> Error for SendError<T> {}
+
+/// An error returned by [`Receiver::changed`] and [`Receiver::has_changed`] when the [`Sender`]
+/// has been dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl Error for RecvError {}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::{block_on, spawn};
+
+    #[test]
+    fn receiver_sees_latest_value() {
+        let (tx, mut rx) = channel(0);
+
+        let seen = block_on(async move {
+            let sender = spawn(async move {
+                tx.send(1).unwrap();
+                tx.send(2).unwrap();
+            });
+
+            rx.changed().await.unwrap();
+            let seen = *rx.borrow_and_update();
+
+            sender.await;
+            assert_eq!(rx.changed().await, Err(RecvError));
+
+            seen
+        });
+
+        assert_eq!(seen, 2);
+    }
+}
@@ -118,6 +118,11 @@
 //! # }
 //! ```
 //!
+//! When writing competition programs, background tasks usually shouldn't outlive the competition
+//! mode that spawned them. Tasks spawned with [`spawn_scoped`] are owned by the current
+//! [`ModeScope`] rather than a [`Task`] handle, so they keep running after the mode's function
+//! returns, but are cancelled as soon as the competition runtime switches to a different mode.
+//!
 //! # Sharing State Between Tasks
 //!
 //! When running multiple tasks at once, it's often useful to share some data between them.
@@ -170,17 +175,511 @@
 //! state across multiple running tasks.
 //!
 //! For more information on how to do this, see vexide's [`sync`](crate::sync) module.
+//!
+//! # Priorities
+//!
+//! Every task has a [`Priority`], which is [`Priority::Normal`] for tasks created with [`spawn`].
+//! Whenever more than one task is ready to run, vexide's runtime polls the ones with the highest
+//! priority first. This is useful for keeping time-sensitive work, like a chassis control loop,
+//! responsive while lower-priority tasks, like drawing to the display, are also busy.
+//!
+//! Tasks with a different priority are spawned using a [`Builder`]:
+//!
+//! ```no_run
+//! # #[vexide::main]
+//! # async fn main(_peripherals: vexide::peripherals::Peripherals) {
+//! use std::time::Duration;
+//!
+//! use vexide::{
+//!     task::{Builder, Priority},
+//!     time::sleep,
+//! };
+//!
+//! let control_loop = Builder::new().priority(Priority::High).spawn(async {
+//!     loop {
+//!         // Update the chassis PID controllers...
+//!         sleep(Duration::from_millis(10)).await;
+//!     }
+//! });
+//! # }
+//! ```
+//!
+//! Lower-priority tasks are never starved completely. If a ready task is repeatedly passed over
+//! for higher-priority work, it will eventually be run anyway.
+//!
+//! # Introspection
+//!
+//! vexide's runtime keeps track of how often and for how long each task is polled. If your robot is
+//! stuttering, the [`snapshot`] function can be used to find which task is spending too long
+//! between `await` points. Giving tasks a [name](Builder::name) makes its output easier to read:
+//!
+//! ```no_run
+//! # #[vexide::main]
+//! # async fn main(_peripherals: vexide::peripherals::Peripherals) {
+//! use vexide::task::{self, Builder};
+//!
+//! Builder::new()
+//!     .name("odometry")
+//!     .spawn(async {
+//!         // ...
+//!     })
+//!     .detach();
+//!
+//! for task in task::snapshot() {
+//!     println!(
+//!         "{}: polled {} times, longest poll took {:?}",
+//!         task.name.as_deref().unwrap_or("<unnamed>"),
+//!         task.poll_count,
+//!         task.longest_poll,
+//!     );
+//! }
+//! # }
+//! ```
+//!
+//! The runtime also keeps track of how often it is idle, waiting for tasks to become ready.
+//! [`executor_stats`] returns these statistics, along with how long each iteration of the runtime's
+//! main loop takes on average. A function can also be run whenever the runtime is idle using
+//! [`set_idle_hook`].
+//!
+//! # Slow Poll Watchdog
+//!
+//! A task that runs for a long time without reaching an `await` point blocks every other task, as
+//! well as the background processing that keeps devices updated. To help track these tasks down,
+//! the runtime can report any poll that takes longer than a threshold set with
+//! [`set_slow_poll_threshold`]:
+//!
+//! ```no_run
+//! # #[vexide::main]
+//! # async fn main(_peripherals: vexide::peripherals::Peripherals) {
+//! use std::time::Duration;
+//!
+//! use vexide::task;
+//!
+//! task::set_slow_poll_threshold(Some(Duration::from_millis(20)));
+//!
+//! task::spawn(async {
+//!     // Oops! This loop never yields, so the watchdog will report this task.
+//!     loop {}
+//! })
+//! .detach();
+//! # }
+//! ```
+//!
+//! # Panic Isolation
+//!
+//! By default, a panic in any task stops the entire program. When the program is built with
+//! `panic = "unwind"`, tasks spawned with `spawn_catching` instead complete with a `TaskPanic`
+//! error when they panic, allowing the rest of the program to keep running.
+//!
+//! <section class="warning">
+//!
+//! Catching a panic requires unwinding the stack. Programs built for the V5 Brain use
+//! `panic = "abort"`, so `spawn_catching` and `TaskPanic` are **not available** there, and a panic
+//! in any task always stops the program. They can be used in simulators and tests running on a
+//! computer.
+//!
+//! </section>
+//!
+//! ```no_run
+//! # #[vexide::main]
+//! # async fn main(_peripherals: vexide::peripherals::Peripherals) {
+//! use vexide::task;
+//!
+//! let telemetry = task::spawn_catching(async {
+//!     let readings: Vec<f64> = Vec::new();
+//!     println!("Latest reading: {}", readings[0]); // Oops!
+//! });
+//!
+//! if let Err(panic) = telemetry.await {
+//!     println!("Telemetry crashed: {panic}");
+//! }
+//! # }
+//! ```
+//!
+//! Caught panics are printed by a wrapper around the panic hook that's installed when the first
+//! catching task is spawned, so custom panic hooks should be set before then.
+
+use std::{
+    cell::Cell,
+    fmt,
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
 
-use std::{future::Future, rc::Rc};
+use vexide_core::{
+    backtrace::Backtrace,
+    competition::{BindingKey, ModeScope},
+};
 
-pub use crate::local::{LocalKey, task_local};
+#[cfg(panic = "unwind")]
+pub use crate::unwind::TaskPanic;
+#[cfg(panic = "unwind")]
+use crate::unwind::CatchUnwind;
 use crate::{executor::EXECUTOR, local::TaskLocalStorage};
+pub use crate::{
+    join_set::JoinSet,
+    local::{LocalKey, task_local},
+};
 
 // public because it's used in Task<T> and InfallibleTask<T>
 #[doc(hidden)]
 #[derive(Debug)]
 pub struct TaskMetadata {
     pub(crate) tls: Rc<TaskLocalStorage>,
+    pub(crate) info: Rc<TaskInfo>,
+}
+
+impl TaskMetadata {
+    /// Returns the unique identifier of this task.
+    #[must_use]
+    pub fn id(&self) -> TaskId {
+        self.info.id
+    }
+
+    /// Returns the name of this task, if it was given one.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.info.name.as_deref()
+    }
+
+    /// Returns the scheduling priority of this task.
+    #[must_use]
+    pub fn priority(&self) -> Priority {
+        self.info.priority
+    }
+
+    /// Returns the location in the source code where this task was spawned.
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.info.location
+    }
+}
+
+/// A unique identifier for a task.
+///
+/// Task IDs are assigned in the order that tasks are spawned and are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(pub(crate) u64);
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Runtime statistics collected by the executor for a single task.
+#[derive(Debug, Default, Clone, Copy)]
+struct TaskStats {
+    poll_count: u64,
+    total_poll_time: Duration,
+    longest_poll: Duration,
+    last_woken: Option<Instant>,
+}
+
+/// Information about a task that is tracked by the executor.
+#[derive(Debug)]
+pub(crate) struct TaskInfo {
+    pub id: TaskId,
+    pub name: Option<String>,
+    pub priority: Priority,
+    pub location: &'static Location<'static>,
+    /// The backtrace of the task's spawn point, only captured while the slow poll watchdog is
+    /// enabled.
+    pub spawn_backtrace: Option<Backtrace>,
+    stats: Cell<TaskStats>,
+}
+
+impl TaskInfo {
+    pub fn new(
+        id: TaskId,
+        name: Option<String>,
+        priority: Priority,
+        location: &'static Location<'static>,
+        spawn_backtrace: Option<Backtrace>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            priority,
+            location,
+            spawn_backtrace,
+            stats: Cell::new(TaskStats::default()),
+        }
+    }
+
+    /// Records that the task was scheduled to run.
+    pub fn record_wake(&self, now: Instant) {
+        let mut stats = self.stats.get();
+        stats.last_woken = Some(now);
+        self.stats.set(stats);
+    }
+
+    /// Records that the task was polled for the given amount of time.
+    pub fn record_poll(&self, elapsed: Duration) {
+        let mut stats = self.stats.get();
+        stats.poll_count += 1;
+        stats.total_poll_time += elapsed;
+        stats.longest_poll = stats.longest_poll.max(elapsed);
+        self.stats.set(stats);
+    }
+
+    pub fn snapshot(&self) -> TaskSnapshot {
+        let stats = self.stats.get();
+
+        TaskSnapshot {
+            id: self.id,
+            name: self.name.clone(),
+            priority: self.priority,
+            location: self.location,
+            poll_count: stats.poll_count,
+            total_poll_time: stats.total_poll_time,
+            longest_poll: stats.longest_poll,
+            last_woken: stats.last_woken,
+        }
+    }
+}
+
+/// The state of a task at the time [`snapshot`] was called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TaskSnapshot {
+    /// The task's unique identifier.
+    pub id: TaskId,
+
+    /// The name given to the task with [`Builder::name`], if any.
+    pub name: Option<String>,
+
+    /// The task's scheduling priority.
+    pub priority: Priority,
+
+    /// The location in the source code where the task was spawned.
+    pub location: &'static Location<'static>,
+
+    /// The number of times the task has been polled.
+    pub poll_count: u64,
+
+    /// The total amount of time spent polling the task.
+    pub total_poll_time: Duration,
+
+    /// The longest amount of time that a single poll of the task took.
+    ///
+    /// Since tasks are scheduled cooperatively, no other task can run during a poll. Large values
+    /// here mean that the task is doing too much work between `await` points.
+    pub longest_poll: Duration,
+
+    /// The last time the task was woken up and scheduled to run, or [`None`] if it has only been
+    /// scheduled when it was spawned.
+    pub last_woken: Option<Instant>,
+}
+
+/// Returns a snapshot of every task that currently exists, in the order they were spawned.
+///
+/// A task exists until it is cancelled, or until it has completed and its [`Task`] handle has
+/// been dropped or awaited. Detached tasks exist until they complete.
+///
+/// The future passed to [`block_on`](crate::block_on) (including a program's `main` function) is
+/// not a task, so it is not included.
+#[must_use]
+pub fn snapshot() -> Vec<TaskSnapshot> {
+    EXECUTOR.with(super::executor::Executor::snapshot)
+}
+
+/// Enables or disables the slow poll watchdog.
+///
+/// While enabled, any task that runs for longer than `threshold` without yielding is reported
+/// over the serial console along with its name and the location it was spawned at. Passing
+/// [`None`] disables the watchdog, which is the default.
+///
+/// The watchdog also captures a [`Backtrace`] whenever a task is spawned, which is included in its
+/// reports. Since this has a cost, backtraces are only available for tasks spawned while the
+/// watchdog is enabled.
+///
+/// See the [module-level documentation](self#slow-poll-watchdog) for more information.
+pub fn set_slow_poll_threshold(threshold: Option<Duration>) {
+    EXECUTOR.with(|ex| ex.slow_poll_threshold.set(threshold));
+}
+
+/// Returns the threshold for the slow poll watchdog, or [`None`] if it is disabled.
+///
+/// See [`set_slow_poll_threshold`] for more information.
+#[must_use]
+pub fn slow_poll_threshold() -> Option<Duration> {
+    EXECUTOR.with(|ex| ex.slow_poll_threshold.get())
+}
+
+/// Statistics about how busy the executor has been.
+///
+/// Each iteration of the executor's main loop lets VEXos update devices, wakes any tasks whose
+/// timers have expired, and polls a task if one is ready. An iteration where no task (or the future
+/// passed to [`block_on`](crate::block_on)) was ready is counted as idle.
+///
+/// These are counts of iterations, not measurements of time. A busy iteration may take much longer
+/// than an idle one, so the idle percentage is a measure of how often the executor had nothing to
+/// do rather than how much of the Brain's CPU time is free. Use
+/// [`average_loop_period`](Self::average_loop_period) to see how quickly the executor is able to
+/// respond to tasks.
+///
+/// Statistics accumulate for the entire run of the program. To measure a shorter window, take the
+/// difference between two sets of statistics using [`ExecutorStats::since`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{prelude::*, task};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let mut last = task::executor_stats();
+///
+///     loop {
+///         sleep(Duration::from_secs(1)).await;
+///
+///         let now = task::executor_stats();
+///         let window = now.since(&last);
+///         last = now;
+///
+///         println!(
+///             "Idle iterations: {:.1}% (loop period {:?})",
+///             window.idle_percentage(),
+///             window.average_loop_period(),
+///         );
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExecutorStats {
+    /// The number of iterations of the executor's main loop.
+    pub iterations: u64,
+
+    /// The number of iterations where nothing was ready to be polled.
+    pub idle_iterations: u64,
+
+    /// The total amount of time spent in the executor's main loop.
+    pub elapsed: Duration,
+}
+
+impl ExecutorStats {
+    pub(crate) const fn new() -> Self {
+        Self {
+            iterations: 0,
+            idle_iterations: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Returns the percentage (from 0 to 100) of loop iterations where nothing was ready to be
+    /// polled.
+    ///
+    /// Returns zero if no iterations have been recorded.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn idle_percentage(&self) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        self.idle_iterations as f64 / self.iterations as f64 * 100.0
+    }
+
+    /// Returns the average amount of time that a single iteration of the executor's main loop
+    /// took.
+    ///
+    /// Returns zero if no iterations have been recorded.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_loop_period(&self) -> Duration {
+        if self.iterations == 0 {
+            return Duration::ZERO;
+        }
+
+        self.elapsed.div_f64(self.iterations as f64)
+    }
+
+    /// Returns the statistics collected between `earlier` and `self`.
+    #[must_use]
+    pub const fn since(&self, earlier: &Self) -> Self {
+        Self {
+            iterations: self.iterations.saturating_sub(earlier.iterations),
+            idle_iterations: self.idle_iterations.saturating_sub(earlier.idle_iterations),
+            elapsed: self.elapsed.saturating_sub(earlier.elapsed),
+        }
+    }
+}
+
+/// Returns statistics about how busy the executor has been since the program started.
+///
+/// See [`ExecutorStats`] for more information.
+#[must_use]
+pub fn executor_stats() -> ExecutorStats {
+    EXECUTOR.with(|ex| ex.stats.get())
+}
+
+/// Registers a function to be called whenever the executor has nothing to do, replacing any
+/// previously registered hook.
+///
+/// The hook is called once for every iteration of the executor's main loop where no task was
+/// ready, so it runs very often and should return quickly. It's useful for low-priority
+/// background work or for measuring how busy the program is.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{cell::Cell, rc::Rc};
+///
+/// use vexide::{prelude::*, task};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let idle_count = Rc::new(Cell::new(0u64));
+///
+///     task::set_idle_hook({
+///         let idle_count = idle_count.clone();
+///         move || idle_count.set(idle_count.get() + 1)
+///     });
+/// }
+/// ```
+pub fn set_idle_hook(hook: impl FnMut() + 'static) {
+    EXECUTOR.with(|ex| ex.replace_idle_hook(Some(Box::new(hook))));
+}
+
+/// Unregisters the current idle hook, returning it.
+///
+/// If called from inside the hook itself, the hook is still unregistered once it returns, but this
+/// returns [`None`] since the hook is in use.
+///
+/// See [`set_idle_hook`] for more information.
+#[allow(clippy::must_use_candidate)]
+pub fn take_idle_hook() -> Option<Box<dyn FnMut()>> {
+    EXECUTOR.with(|ex| ex.replace_idle_hook(None))
+}
+
+/// The scheduling priority of a task.
+///
+/// When more than one task is ready to run, tasks with a higher priority are polled first. See the
+/// [module-level documentation](self#priorities) for more information.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// For background work that may be delayed, such as logging or drawing to the display.
+    Low,
+
+    /// The default priority, used by [`spawn`].
+    #[default]
+    Normal,
+
+    /// For time-sensitive work, such as control loops.
+    High,
+}
+
+impl Priority {
+    /// The number of priority levels.
+    pub(crate) const COUNT: usize = 3;
 }
 
 /// A spawned task.
@@ -215,6 +714,291 @@ pub type Task<T> = async_task::Task<T, TaskMetadata>;
 pub type FallibleTask<T> = async_task::FallibleTask<T, TaskMetadata>;
 
 /// Spawns a new async task that can be controlled with the returned task handle.
+///
+/// The task is spawned with [`Priority::Normal`]. To configure the task before spawning it, use a
+/// [`Builder`].
+#[track_caller]
 pub fn spawn<T>(future: impl Future<Output = T> + 'static) -> Task<T> {
-    EXECUTOR.with(|ex| ex.spawn(future))
+    Builder::new().spawn(future)
+}
+
+/// Spawns a new async task that catches panics, returning a handle to it.
+///
+/// If the task panics, it completes with a [`TaskPanic`] error instead of stopping the program.
+/// See the [module-level documentation](self#panic-isolation) for more information.
+///
+/// The task is spawned with [`Priority::Normal`]. To configure the task before spawning it, use a
+/// [`Builder`].
+///
+/// # Platform Support
+///
+/// Panics can only be caught when the program is built with `panic = "unwind"`, so this function
+/// is only available in that case. Programs built for the V5 Brain (`armv7a-vex-v5`) use
+/// `panic = "abort"`, where it can't be used.
+///
+/// # Panic Hooks
+///
+/// The first call to this function wraps whatever panic hook is installed at the time, so that
+/// caught panics are printed and recorded instead of being passed to that hook. Any custom hook
+/// (including the one installed by `vexide-startup`) must be set before the first catching task
+/// is spawned. A hook set afterwards replaces the wrapper, and caught panics will be passed to it
+/// as well.
+#[cfg(panic = "unwind")]
+#[track_caller]
+pub fn spawn_catching<T>(future: impl Future<Output = T> + 'static) -> Task<Result<T, TaskPanic>> {
+    Builder::new().spawn_catching(future)
+}
+
+/// Spawns a new async task that is cancelled when the competition runtime leaves the current mode,
+/// returning its ID.
+///
+/// The task is owned by the current [`ModeScope`] instead of a [`Task`] handle, so it runs in the
+/// background until it finishes or the mode ends, whichever happens first. Tasks spawned with this
+/// function can spawn scoped tasks of their own, which are tied to the same mode.
+///
+/// The task is spawned with [`Priority::Normal`]. To configure the task before spawning it, use a
+/// [`Builder`].
+///
+/// # Panics
+///
+/// Panics if called from outside of a competition mode, such as from a task spawned with
+/// [`spawn`] or before the competition runtime has started.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{prelude::*, task::spawn_scoped};
+///
+/// struct Robot {
+///     intake: Motor,
+/// }
+///
+/// impl Compete for Robot {
+///     async fn autonomous(&mut self) {
+///         spawn_scoped(async {
+///             loop {
+///                 println!("Intaking...");
+///                 sleep(Duration::from_millis(100)).await;
+///             }
+///         });
+///
+///         // The task above keeps running until autonomous ends, rather than into driver control.
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn spawn_scoped(future: impl Future + 'static) -> TaskId {
+    Builder::new().spawn_scoped(future)
+}
+
+/// A future that runs inside of a [`ModeScope`], allowing it to spawn more scoped tasks.
+struct Scoped<F> {
+    scope: ModeScope,
+    /// The key that the future's task is bound to the scope with, which is set once the task has
+    /// been spawned.
+    binding: Rc<Cell<Option<BindingKey>>>,
+    future: F,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is structurally pinned and is never moved out of `self`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        let poll = this.scope.enter(|| future.poll(cx));
+
+        // There's no need for the scope to keep a finished task around until the mode ends.
+        if poll.is_ready()
+            && let Some(key) = this.binding.take()
+        {
+            this.scope.unbind(key);
+        }
+
+        poll
+    }
+}
+
+/// Task factory, which can be used to configure the properties of a new task.
+///
+/// Methods can be chained on it to configure the task, and [`Builder::spawn`] takes ownership of
+/// the builder to create the task.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::{
+///     prelude::*,
+///     task::{Builder, Priority},
+/// };
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let task = Builder::new()
+///         .priority(Priority::Low)
+///         .spawn(async { 1 + 2 });
+///
+///     assert_eq!(task.await, 3);
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+#[must_use = "a builder does nothing unless you call `spawn` on it"]
+pub struct Builder {
+    name: Option<String>,
+    priority: Priority,
+}
+
+impl Builder {
+    /// Creates a new task builder with the default configuration.
+    pub const fn new() -> Self {
+        Self {
+            name: None,
+            priority: Priority::Normal,
+        }
+    }
+
+    /// Names the new task.
+    ///
+    /// The name is included in task [snapshots](snapshot) for debugging purposes.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the scheduling priority of the new task.
+    pub const fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Spawns a new async task with this builder's configuration, returning a handle to it.
+    #[track_caller]
+    pub fn spawn<T>(self, future: impl Future<Output = T> + 'static) -> Task<T> {
+        let location = Location::caller();
+        EXECUTOR.with(|ex| ex.spawn(future, self.name, self.priority, location))
+    }
+
+    /// Spawns a new async task with this builder's configuration that catches panics, returning a
+    /// handle to it.
+    ///
+    /// See [`spawn_catching`] for more information.
+    #[cfg(panic = "unwind")]
+    #[track_caller]
+    pub fn spawn_catching<T>(
+        self,
+        future: impl Future<Output = T> + 'static,
+    ) -> Task<Result<T, TaskPanic>> {
+        self.spawn(CatchUnwind::new(future))
+    }
+
+    /// Spawns a new async task with this builder's configuration that is cancelled when the
+    /// competition runtime leaves the current mode, returning its ID.
+    ///
+    /// See [`spawn_scoped`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from outside of a competition mode.
+    #[track_caller]
+    pub fn spawn_scoped(self, future: impl Future + 'static) -> TaskId {
+        let Some(scope) = ModeScope::current() else {
+            panic!("`spawn_scoped` must be called from within a competition mode");
+        };
+
+        let binding = Rc::new(Cell::new(None));
+        let task = self.spawn(Scoped {
+            scope: scope.clone(),
+            binding: binding.clone(),
+            future,
+        });
+        let id = task.metadata().id();
+
+        binding.set(Some(scope.bind(task)));
+        id
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::ControlFlow;
+
+    use vex_sdk_mock as _;
+    use vexide_core::competition::CompetitionRuntime;
+
+    use super::*;
+    use crate::{block_on, time::sleep};
+
+    #[test]
+    fn scoped_tasks_stop_with_mode() {
+        let polled = Rc::new(Cell::new(0));
+
+        block_on(async {
+            CompetitionRuntime::builder(polled.clone())
+                .while_driving(|polled| {
+                    let polled = polled.clone();
+                    Box::pin(async move {
+                        spawn_scoped(async move {
+                            loop {
+                                polled.set(polled.get() + 1);
+                                sleep(Duration::from_millis(1)).await;
+                            }
+                        });
+
+                        sleep(Duration::from_millis(5)).await;
+                        ControlFlow::Break(())
+                    })
+                })
+                .await;
+
+            let polled_before = polled.get();
+            sleep(Duration::from_millis(5)).await;
+
+            assert!(polled_before > 0);
+            assert_eq!(polled.get(), polled_before);
+        });
+    }
+
+    #[test]
+    fn finished_scoped_tasks_are_released() {
+        block_on(async {
+            CompetitionRuntime::builder(())
+                .while_driving(|()| {
+                    Box::pin(async {
+                        let id = spawn_scoped(async {});
+                        sleep(Duration::from_millis(1)).await;
+
+                        // The scope is still active, but it shouldn't be holding on to the task.
+                        assert!(ModeScope::current().is_some_and(|scope| scope.is_active()));
+                        assert!(snapshot().iter().all(|task| task.id != id));
+
+                        ControlFlow::Break(())
+                    })
+                })
+                .await;
+        });
+    }
+
+    #[test]
+    fn slow_poll_threshold_captures_spawn_backtraces() {
+        assert_eq!(slow_poll_threshold(), None);
+        let untraced = spawn(async {});
+        assert!(untraced.metadata().info.spawn_backtrace.is_none());
+
+        set_slow_poll_threshold(Some(Duration::from_millis(1)));
+        assert_eq!(slow_poll_threshold(), Some(Duration::from_millis(1)));
+        let traced = spawn(async {});
+        assert!(traced.metadata().info.spawn_backtrace.is_some());
+
+        set_slow_poll_threshold(None);
+        assert_eq!(slow_poll_threshold(), None);
+
+        block_on(async {
+            untraced.await;
+            traced.await;
+        });
+    }
 }
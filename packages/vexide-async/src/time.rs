@@ -5,16 +5,65 @@
 //! - [`Sleep`] is a future that does no work and completes at a specific [`Instant`] in time.
 //! - [`sleep`] and [`sleep_until`] provide ways to yield control away from a future for or until a
 //!   specific instant in time.
+//! - [`timeout`] and [`timeout_at`] limit the amount of time that a future is allowed to run for,
+//!   returning an [`Elapsed`] error if it doesn't complete in time.
+//! - [`Interval`] (created by [`interval`] and [`interval_at`]) yields at a fixed period, which is
+//!   useful for running control loops at a consistent rate.
+//!
+//! # Testing
+//!
+//! With the `test-util` feature enabled, the runtime's clock can be frozen using [`pause`]. While
+//! the clock is paused, time only moves forward when [`advance`] is called, or when every task is
+//! waiting on a timer, in which case the runtime skips ahead to the next timer's deadline. This
+//! allows tests of time-dependent code to run instantly and deterministically:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use vexide::{prelude::*, time};
+//!
+//! #[vexide::test]
+//! async fn waits_for_ten_minutes(_peripherals: Peripherals) {
+//!     time::pause();
+//!     let start = time::now();
+//!
+//!     // This completes immediately.
+//!     sleep(Duration::from_mins(10)).await;
+//!
+//!     assert_eq!(time::now() - start, Duration::from_mins(10));
+//! }
+//! ```
+//!
+//! The clock is local to the current thread, so tests running in parallel do not affect each other.
+//! Use [`now`] rather than [`Instant::now`] to read the paused clock.
 
 use core::{
+    error::Error,
+    fmt,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
     time::Duration,
 };
-use std::{task::Waker, time::Instant};
+use std::time::Instant;
+
+#[cfg(feature = "test-util")]
+pub use vexide_core::time::{advance, is_paused, pause, resume};
+
+use crate::{executor::EXECUTOR, reactor::TimerKey};
 
-use crate::{executor::EXECUTOR, reactor::Sleeper};
+/// Returns the current time according to the runtime's clock.
+///
+/// This is the same as [`Instant::now`], unless the clock has been paused or advanced using the
+/// `test-util` feature. All of the timers in this module use this clock.
+#[must_use]
+pub fn now() -> Instant {
+    #[cfg(feature = "test-util")]
+    return vexide_core::time::now();
+
+    #[cfg(not(feature = "test-util"))]
+    return Instant::now();
+}
 
 /// A future that will complete after a certain instant is reached in time.
 ///
@@ -23,51 +72,73 @@ use crate::{executor::EXECUTOR, reactor::Sleeper};
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Sleep {
     deadline: Instant,
-    registered_waker: Option<Waker>,
+    timer: Option<TimerKey>,
+}
+
+impl Sleep {
+    /// Returns the instant at which this future will complete.
+    #[must_use]
+    pub const fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Changes the instant at which this future will complete.
+    ///
+    /// This may be called after the future has already completed, in which case it may be polled
+    /// again and will complete once the new deadline is reached.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+
+        // The old timer is for the wrong deadline, so the next poll needs to register a new one.
+        self.deregister();
+    }
+
+    /// Removes this future's timer from the reactor, if it has one.
+    fn deregister(&mut self) {
+        if let Some(key) = self.timer.take() {
+            // The executor may have already been destroyed if this is being dropped along with it.
+            _ = EXECUTOR.try_with(|ex| ex.remove_timer(key));
+        }
+    }
 }
 
 impl Future for Sleep {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if Instant::now() >= self.deadline {
+        let this = self.get_mut();
+
+        if now() >= this.deadline {
+            this.deregister();
             return Poll::Ready(());
         }
 
-        // Register a waker on the reactor to only poll this future when the deadline passes.
-        //
-        // We should only push to the sleeper queue if we either haven't pushed
-        // (`self.registered.waker == None`) or if !w.will_wake(cx.waker()), meaning the already
-        // registered waker will not wake up the same task as the current waker indicating that the
-        // sleep has potentially been moved across executors.
-        if self
-            .registered_waker
-            .as_ref()
-            .is_none_or(|w| !w.will_wake(cx.waker()))
-        {
-            let this = self.get_mut();
-            this.registered_waker = Some(cx.waker().clone());
-
-            EXECUTOR.with(|ex| {
-                ex.with_reactor(|reactor| {
-                    reactor.sleepers.push(Sleeper {
-                        deadline: this.deadline,
-                        waker: cx.waker().clone(),
-                    });
-                });
+        // Register a timer on the reactor to only poll this future when the deadline passes. If we
+        // already have one, it's updated in place in case this future has been moved to a
+        // different task since it was last polled.
+        EXECUTOR.with(|ex| {
+            ex.with_reactor(|reactor| match this.timer {
+                Some(key) if reactor.update_timer(key, cx.waker()) => {}
+                _ => this.timer = Some(reactor.insert_timer(this.deadline, cx.waker().clone())),
             });
-        }
+        });
 
         Poll::Pending
     }
 }
 
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        self.deregister();
+    }
+}
+
 /// Waits until `duration` has elapsed.
 ///
 /// This function returns a future that will complete after the given duration, effectively yielding
 /// the current task for a period of time.
 ///
-/// Equivalent to `sleep_until(Instant::now() + duration)`.
+/// Equivalent to `sleep_until(now() + duration)`, where [`now`] is the runtime's clock.
 ///
 /// # Examples
 ///
@@ -85,8 +156,8 @@ impl Future for Sleep {
 /// ```
 pub fn sleep(duration: Duration) -> Sleep {
     Sleep {
-        deadline: Instant::now() + duration,
-        registered_waker: None,
+        deadline: now() + duration,
+        timer: None,
     }
 }
 
@@ -115,6 +186,451 @@ pub fn sleep(duration: Duration) -> Sleep {
 pub const fn sleep_until(deadline: Instant) -> Sleep {
     Sleep {
         deadline,
-        registered_waker: None,
+        timer: None,
+    }
+}
+
+/// A future that limits the amount of time another future is allowed to run for.
+///
+/// This type is returned by the [`timeout`] and [`timeout_at`] functions.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Timeout<F> {
+    future: F,
+    sleep: Sleep,
+}
+
+impl<F> Timeout<F> {
+    /// Returns the instant at which this timeout will elapse.
+    pub const fn deadline(&self) -> Instant {
+        self.sleep.deadline
+    }
+
+    /// Returns a reference to the underlying future.
+    pub const fn get_ref(&self) -> &F {
+        &self.future
+    }
+
+    /// Returns a mutable reference to the underlying future.
+    pub const fn get_mut(&mut self) -> &mut F {
+        &mut self.future
+    }
+
+    /// Consumes this timeout, returning the underlying future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is structurally pinned and is never moved out of `self`. `sleep` is
+        // `Unpin`, so it is fine to hand out a mutable reference to it.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        // Give the future a chance to complete before checking the deadline, so that a future
+        // which is immediately ready never times out.
+        if let Poll::Ready(output) = future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        Pin::new(&mut this.sleep)
+            .poll(cx)
+            .map(|()| Err(Elapsed(())))
+    }
+}
+
+/// An error returned when a [`Timeout`] elapses before its future completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl Error for Elapsed {}
+
+/// Requires a future to complete before `duration` has elapsed.
+///
+/// If the future completes in time, its output is returned in [`Ok`]. Otherwise, the future is
+/// dropped (cancelling it) and an [`Elapsed`] error is returned.
+///
+/// Equivalent to `timeout_at(now() + duration, future)`, where [`now`] is the runtime's clock.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{prelude::*, time::timeout};
+///
+/// async fn wait_for_object(sensor: &DistanceSensor) {
+///     while !sensor.object().is_ok_and(|object| object.is_some()) {
+///         sleep(DistanceSensor::UPDATE_INTERVAL).await;
+///     }
+/// }
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let sensor = DistanceSensor::new(peripherals.port_1);
+///
+///     match timeout(Duration::from_secs(3), wait_for_object(&sensor)).await {
+///         Ok(()) => println!("Found an object!"),
+///         Err(_) => println!("Nothing showed up within 3 seconds."),
+///     }
+/// }
+/// ```
+pub fn timeout<F: Future>(duration: Duration, future: F) -> Timeout<F> {
+    Timeout {
+        future,
+        sleep: sleep(duration),
+    }
+}
+
+/// Requires a future to complete before `deadline` is reached.
+///
+/// If the future completes in time, its output is returned in [`Ok`]. Otherwise, the future is
+/// dropped (cancelling it) and an [`Elapsed`] error is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::{Duration, Instant};
+///
+/// use vexide::{prelude::*, time::timeout_at};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let deadline = Instant::now() + Duration::from_secs(15);
+///
+///     // Both steps of this routine share the same 15 second deadline.
+///     let first = timeout_at(deadline, sleep(Duration::from_secs(5))).await;
+///     let second = timeout_at(deadline, sleep(Duration::from_secs(20))).await;
+///
+///     assert!(first.is_ok());
+///     assert!(second.is_err());
+/// }
+/// ```
+pub const fn timeout_at<F: Future>(deadline: Instant, future: F) -> Timeout<F> {
+    Timeout {
+        future,
+        sleep: sleep_until(deadline),
+    }
+}
+
+/// Defines how an [`Interval`] behaves when it misses a tick.
+///
+/// An interval misses a tick when more than one full period passes between two calls to
+/// [`Interval::tick`], which usually happens when the code running between ticks takes longer than
+/// the interval's period to complete.
+///
+/// The default behavior is [`MissedTickBehavior::Burst`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Ticks as fast as possible until the interval has caught up.
+    ///
+    /// Missed ticks complete immediately, one after another, until the interval is back on its
+    /// original schedule. Ticks continue to happen at multiples of the period after the interval's
+    /// start time.
+    #[default]
+    Burst,
+
+    /// Starts a fresh schedule from the time of the late tick.
+    ///
+    /// The next tick happens one full period after the late tick was observed, so every tick after
+    /// a missed one is shifted later by however late the interval was.
+    Delay,
+
+    /// Skips any missed ticks and waits for the next multiple of the period.
+    ///
+    /// Ticks continue to happen at multiples of the period after the interval's start time, but
+    /// ticks that were missed are dropped rather than made up for.
+    Skip,
+}
+
+impl MissedTickBehavior {
+    /// Computes the deadline of the tick following one that was scheduled for `deadline` but was
+    /// only observed at `now`.
+    fn next_deadline(self, deadline: Instant, now: Instant, period: Duration) -> Instant {
+        match self {
+            Self::Burst => deadline + period,
+            Self::Delay => now + period,
+            Self::Skip => {
+                let period_nanos = period.as_nanos();
+                let behind_nanos = now.duration_since(deadline).as_nanos();
+                let into_period = Duration::from_nanos((behind_nanos % period_nanos) as u64);
+
+                now + period.saturating_sub(into_period)
+            }
+        }
+    }
+}
+
+/// A timer that ticks at a fixed period.
+///
+/// An `Interval` is created using the [`interval`] or [`interval_at`] functions, and ticks are
+/// awaited using [`Interval::tick`].
+///
+/// Unlike calling [`sleep`] at the end of every loop iteration, an interval schedules its deadlines
+/// at fixed multiples of its period from when it started, so the time spent doing work between
+/// ticks doesn't cause the loop to drift. If the work takes longer than a full period, the
+/// interval's [`MissedTickBehavior`] decides how it catches up.
+///
+/// # Examples
+///
+/// Running a control loop once every time a motor reports new data:
+///
+/// ```no_run
+/// use vexide::{prelude::*, time::interval};
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let motor = Motor::new(peripherals.port_1, Gearset::Green, Direction::Forward);
+///     let mut interval = interval(Motor::UPDATE_INTERVAL);
+///
+///     loop {
+///         interval.tick().await;
+///
+///         println!("{:?}", motor.velocity());
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Interval {
+    sleep: Sleep,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+impl Interval {
+    /// Waits until the next tick of the interval, returning the instant that the tick was
+    /// scheduled for.
+    ///
+    /// The first tick completes immediately if the interval was created with [`interval`], or at
+    /// the start time if it was created with [`interval_at`].
+    ///
+    /// # Cancel Safety
+    ///
+    /// Dropping the returned future before it completes does not cause a tick to be missed, so this
+    /// method is safe to use in [`select!`](crate::future::select).
+    pub async fn tick(&mut self) -> Instant {
+        core::future::poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next tick of the interval.
+    ///
+    /// This is the poll-based equivalent of [`Interval::tick`], and is useful when implementing
+    /// futures or streams by hand. When this returns [`Poll::Pending`], the current task is
+    /// scheduled to be woken up at the next tick.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        if Pin::new(&mut self.sleep).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let deadline = self.sleep.deadline();
+        let now = now();
+
+        // If we're more than a full period late, at least one tick was missed.
+        let next_deadline = if now >= deadline + self.period {
+            self.missed_tick_behavior
+                .next_deadline(deadline, now, self.period)
+        } else {
+            deadline + self.period
+        };
+
+        self.sleep.reset(next_deadline);
+
+        Poll::Ready(deadline)
+    }
+
+    /// Resets the interval so that the next tick happens one period from now.
+    pub fn reset(&mut self) {
+        self.sleep.reset(now() + self.period);
+    }
+
+    /// Returns the period of the interval.
+    #[must_use]
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the behavior of the interval when it misses a tick.
+    #[must_use]
+    pub const fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Changes the behavior of the interval when it misses a tick.
+    pub const fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+}
+
+/// Creates a new [`Interval`] that ticks every `period`, with the first tick completing
+/// immediately.
+///
+/// Equivalent to `interval_at(now(), period)`, where [`now`] is the runtime's clock.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{prelude::*, time::interval};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let mut interval = interval(Duration::from_millis(10));
+///
+///     interval.tick().await; // Completes immediately.
+///     interval.tick().await; // Completes 10ms after the first tick.
+///     interval.tick().await; // Completes 20ms after the first tick.
+/// }
+/// ```
+#[must_use]
+pub fn interval(period: Duration) -> Interval {
+    interval_at(now(), period)
+}
+
+/// Creates a new [`Interval`] that ticks every `period`, with the first tick completing at
+/// `start`.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::{Duration, Instant};
+///
+/// use vexide::{prelude::*, time::interval_at};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let start = Instant::now() + Duration::from_secs(1);
+///     let mut interval = interval_at(start, Duration::from_millis(10));
+///
+///     interval.tick().await; // Completes after 1 second.
+///     interval.tick().await; // Completes 10ms after that.
+/// }
+/// ```
+#[must_use]
+pub fn interval_at(start: Instant, period: Duration) -> Interval {
+    assert!(!period.is_zero(), "`period` must be non-zero");
+
+    Interval {
+        sleep: sleep_until(start),
+        period,
+        missed_tick_behavior: MissedTickBehavior::default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::block_on;
+
+    #[test]
+    fn timeout_completes_in_time() {
+        let result = block_on(timeout(Duration::from_secs(10), async { 1 }));
+
+        assert_eq!(result, Ok(1));
+    }
+
+    #[test]
+    fn timeout_elapses() {
+        let result = block_on(timeout(
+            Duration::from_millis(1),
+            sleep(Duration::from_secs(10)),
+        ));
+
+        assert_eq!(result, Err(Elapsed(())));
+    }
+
+    #[test]
+    fn dropped_sleeps_deregister() {
+        block_on(async {
+            for _ in 0..100 {
+                _ = timeout(Duration::from_millis(1), sleep(Duration::from_secs(10))).await;
+            }
+        });
+
+        let timers = EXECUTOR.with(|ex| ex.with_reactor(|reactor| reactor.timer_count()));
+        assert_eq!(timers, 0);
+    }
+
+    #[test]
+    fn missed_tick_behaviors() {
+        let period = Duration::from_millis(10);
+        let deadline = Instant::now();
+        let now = deadline + Duration::from_millis(25);
+
+        assert_eq!(
+            MissedTickBehavior::Burst.next_deadline(deadline, now, period),
+            deadline + period
+        );
+        assert_eq!(
+            MissedTickBehavior::Delay.next_deadline(deadline, now, period),
+            now + period
+        );
+        assert_eq!(
+            MissedTickBehavior::Skip.next_deadline(deadline, now, period),
+            deadline + Duration::from_millis(30)
+        );
+    }
+
+    #[test]
+    fn interval_ticks_at_fixed_period() {
+        let start = Instant::now();
+        let period = Duration::from_millis(2);
+
+        let ticks = block_on(async {
+            let mut interval = interval_at(start, period);
+            [
+                interval.tick().await,
+                interval.tick().await,
+                interval.tick().await,
+            ]
+        });
+
+        assert_eq!(ticks, [start, start + period, start + period * 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "test-util")]
+    fn paused_clock_skips_sleeps() {
+        pause();
+        let start = now();
+
+        let ticks = block_on(async {
+            let task = crate::spawn(sleep(Duration::from_mins(1)));
+
+            let mut interval = interval(Duration::from_hours(1));
+            let ticks = [interval.tick().await, interval.tick().await];
+
+            task.await;
+            ticks
+        });
+
+        assert_eq!(ticks, [start, start + Duration::from_hours(1)]);
+        assert_eq!(now() - start, Duration::from_hours(1));
+
+        advance(Duration::from_secs(1));
+        assert_eq!(
+            now() - start,
+            Duration::from_hours(1) + Duration::from_secs(1)
+        );
     }
 }
@@ -0,0 +1,203 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    error::Error,
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::Once,
+    task::{Context, Poll},
+};
+
+use vexide_core::backtrace::Backtrace;
+
+thread_local! {
+    /// The number of [`CatchUnwind`] futures currently being polled on this thread.
+    static CATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    /// Information about the last panic caught by the hook installed by [`install_hook`].
+    static CAUGHT: RefCell<Option<(String, Backtrace)>> = const { RefCell::new(None) };
+}
+
+/// Wraps the current panic hook so that panics inside of a [`CatchUnwind`] future are printed and
+/// recorded rather than being passed on to it.
+///
+/// This only runs once, so it wraps whichever hook is installed when the first [`CatchUnwind`] is
+/// created. Hooks set after that point replace the wrapper entirely.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if CATCH_DEPTH.with(Cell::get) > 0 {
+                eprintln!("{info}");
+
+                let location = info.location().map(ToString::to_string).unwrap_or_default();
+                CAUGHT.with_borrow_mut(|caught| {
+                    *caught = Some((location, Backtrace::capture()));
+                });
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// A future that catches panics from the future it wraps.
+#[derive(Debug)]
+pub(crate) struct CatchUnwind<F> {
+    future: Option<F>,
+}
+
+impl<F: Future> CatchUnwind<F> {
+    pub fn new(future: F) -> Self {
+        install_hook();
+        Self {
+            future: Some(future),
+        }
+    }
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, TaskPanic>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is never moved out of its `Option`, only dropped in place.
+        let mut future = unsafe { self.map_unchecked_mut(|this| &mut this.future) };
+        let Some(inner) = future.as_mut().as_pin_mut() else {
+            panic!("`CatchUnwind` polled after completion");
+        };
+
+        CATCH_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx)));
+        CATCH_DEPTH.with(|depth| depth.set(depth.get() - 1));
+
+        match result {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => {
+                // The future may be in an invalid state after panicking, so get rid of it now.
+                future.set(None);
+
+                let (location, backtrace) = CAUGHT
+                    .with_borrow_mut(Option::take)
+                    .unwrap_or_else(|| (String::new(), Backtrace::capture()));
+
+                Poll::Ready(Err(TaskPanic {
+                    payload,
+                    location,
+                    backtrace,
+                }))
+            }
+        }
+    }
+}
+
+/// An error returned by a task spawned with [`spawn_catching`](crate::task::spawn_catching) when
+/// the task panics.
+///
+/// This contains the panic's message, where it happened, and a backtrace captured at the time of
+/// the panic.
+///
+/// This type is only available when the program is built with `panic = "unwind"`, which isn't
+/// the case on the V5 Brain.
+pub struct TaskPanic {
+    payload: Box<dyn Any + Send + 'static>,
+    location: String,
+    backtrace: Backtrace,
+}
+
+impl TaskPanic {
+    /// Returns the message that the task panicked with, if it was a string.
+    ///
+    /// This is the case for panics created with the [`panic!`] macro, as well as methods like
+    /// [`Option::unwrap`].
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        if let Some(message) = self.payload.downcast_ref::<&'static str>() {
+            Some(message)
+        } else if let Some(message) = self.payload.downcast_ref::<String>() {
+            Some(message)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the location in the source code that the task panicked at, if it is known.
+    #[must_use]
+    pub fn location(&self) -> Option<&str> {
+        (!self.location.is_empty()).then_some(self.location.as_str())
+    }
+
+    /// Returns a backtrace captured at the time of the panic.
+    ///
+    /// See [`Backtrace`] for the platforms on which backtraces are supported.
+    #[must_use]
+    pub const fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// Consumes the error, returning the payload that the task panicked with.
+    ///
+    /// This can be passed to [`std::panic::resume_unwind`] to continue panicking.
+    #[must_use]
+    pub fn into_payload(self) -> Box<dyn Any + Send + 'static> {
+        self.payload
+    }
+}
+
+impl fmt::Debug for TaskPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskPanic")
+            .field("message", &self.message())
+            .field("location", &self.location())
+            .field("backtrace", &self.backtrace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for TaskPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task panicked")?;
+
+        if let Some(location) = self.location() {
+            write!(f, " at {location}")?;
+        }
+        if let Some(message) = self.message() {
+            write!(f, ":\n{message}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for TaskPanic {}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use crate::{block_on, task::spawn_catching};
+
+    #[test]
+    fn catches_task_panics() {
+        let (ok, err) = block_on(async {
+            let ok = spawn_catching(async { 1 }).await;
+            let err = spawn_catching(async {
+                panic!("oh no");
+            })
+            .await;
+
+            (ok, err)
+        });
+
+        assert_eq!(ok.unwrap(), 1);
+
+        let err: super::TaskPanic = err.unwrap_err();
+        assert_eq!(err.message(), Some("oh no"));
+        assert!(err.location().is_some_and(|loc| loc.contains("unwind.rs")));
+    }
+}
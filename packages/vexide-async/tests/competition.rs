@@ -0,0 +1,190 @@
+//! Runs a competition program through a simulated match.
+
+use std::{cell::RefCell, ops::ControlFlow, rc::Rc, time::Duration};
+
+use vex_sdk_mock as _;
+use vexide_async::{block_on, spawn, time};
+use vexide_core::competition::{
+    AutonSelector, Compete, CompeteExt, CompetitionMode, CompetitionRuntime, SelectorInput,
+    SelectorUi, sim::Timeline,
+};
+
+struct Robot {
+    log: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl Compete for Robot {
+    async fn connected(&mut self) {
+        self.log.borrow_mut().push("connected");
+    }
+
+    async fn disabled(&mut self) {
+        self.log.borrow_mut().push("disabled");
+    }
+
+    async fn autonomous(&mut self) {
+        self.log.borrow_mut().push("autonomous");
+    }
+
+    async fn driver(&mut self) {
+        self.log.borrow_mut().push("driver");
+    }
+}
+
+/// A selector UI that records what it draws and plays back a list of inputs.
+struct ScriptedUi {
+    drawn: Rc<RefCell<Vec<usize>>>,
+    inputs: Vec<SelectorInput>,
+}
+
+impl SelectorUi for ScriptedUi {
+    fn draw(&mut self, _routines: &[&str], selected: usize) {
+        self.drawn.borrow_mut().push(selected);
+    }
+
+    fn poll_input(&mut self, _routine_count: usize) -> Option<SelectorInput> {
+        (!self.inputs.is_empty()).then(|| self.inputs.remove(0))
+    }
+}
+
+#[test]
+fn runs_each_mode_of_a_match() {
+    time::pause();
+    let start = time::now();
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    block_on(async {
+        let _robot = spawn(Robot { log: log.clone() }.compete());
+        Timeline::vrc_match().play(time::sleep).await;
+    });
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            "connected",
+            "disabled",
+            "autonomous",
+            "disabled",
+            "driver",
+            "disabled"
+        ]
+    );
+    assert_eq!(time::now() - start, Timeline::vrc_match().duration());
+}
+
+#[test]
+fn selector_runs_chosen_routine() {
+    time::pause();
+
+    let drawn = Rc::new(RefCell::new(Vec::new()));
+    let selector = AutonSelector::new()
+        .routine("left", |log: &mut Vec<_>| {
+            Box::pin(async move { log.push("left") })
+        })
+        .routine("right", |log: &mut Vec<_>| {
+            Box::pin(async move { log.push("right") })
+        })
+        .routine("skills", |log: &mut Vec<_>| {
+            Box::pin(async move { log.push("skills") })
+        });
+    let ui = ScriptedUi {
+        drawn: drawn.clone(),
+        inputs: vec![
+            SelectorInput::Next,
+            SelectorInput::Select(2),
+            SelectorInput::Previous,
+        ],
+    };
+
+    let log = block_on(async {
+        let robot = spawn(
+            CompetitionRuntime::builder(Vec::new())
+                .auton_selector(selector.clone(), ui, time::sleep)
+                .while_driving(|log| Box::pin(async move { ControlFlow::Break(log.clone()) }))
+                .finish(),
+        );
+
+        Timeline::new()
+            .disabled(Duration::from_secs(1))
+            .autonomous(Duration::from_secs(1))
+            .driver(Duration::from_secs(1))
+            .play(time::sleep)
+            .await;
+
+        robot.await
+    });
+
+    assert_eq!(selector.selected_name(), Some("right"));
+    assert_eq!(*drawn.borrow(), [0, 1, 2, 1]);
+    assert_eq!(log, ["right"]);
+}
+
+#[test]
+fn hooks_run_in_order_and_record_history() {
+    time::pause();
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let runtime = CompetitionRuntime::builder(log.clone())
+        .on_exit(|log, mode| log.borrow_mut().push(format!("exit {mode:?}")))
+        .on_transition(|log, from, to| {
+            log.borrow_mut()
+                .push(format!("transition {from:?} -> {to:?}"));
+        })
+        .while_autonomous(|log| {
+            Box::pin(async move {
+                log.borrow_mut().push("autonomous".to_string());
+                ControlFlow::Continue(())
+            })
+        })
+        .while_driving(|log| {
+            Box::pin(async move {
+                log.borrow_mut().push("driver".to_string());
+                ControlFlow::Break(())
+            })
+        })
+        .finish();
+    let history = runtime.history();
+
+    block_on(async {
+        let robot = spawn(runtime);
+
+        Timeline::new()
+            .disabled(Duration::from_secs(1))
+            .autonomous(Duration::from_secs(15))
+            .driver(Duration::from_secs(1))
+            .play(time::sleep)
+            .await;
+
+        robot.await;
+    });
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            "exit Disabled",
+            "transition Disabled -> Autonomous",
+            "autonomous",
+            "exit Autonomous",
+            "transition Autonomous -> Driver",
+            "driver",
+        ]
+    );
+
+    let transitions = history.to_vec();
+    assert_eq!(
+        transitions
+            .iter()
+            .map(|transition| (transition.from, transition.to))
+            .collect::<Vec<_>>(),
+        [
+            (CompetitionMode::Disabled, CompetitionMode::Autonomous),
+            (CompetitionMode::Autonomous, CompetitionMode::Driver),
+        ]
+    );
+    assert_eq!(
+        transitions[1]
+            .timestamp
+            .saturating_sub(transitions[0].timestamp),
+        Duration::from_secs(15)
+    );
+}
@@ -0,0 +1,524 @@
+use alloc::{vec, vec::Vec};
+use core::{error::Error, fmt};
+
+use super::Backtrace;
+use crate::{
+    os::{Version, system_version},
+    program::{CodeSignature, code_signature},
+};
+
+/// The magic bytes at the start of every encoded backtrace.
+const MAGIC: [u8; 4] = *b"VXBT";
+
+/// The current version of the encoding.
+const VERSION: u8 = 1;
+
+/// The size of everything before the frame count.
+const HEADER_SIZE: usize = 29;
+
+/// The largest number of bytes that a varint can take.
+const MAX_VARINT_SIZE: usize = 10;
+
+/// Returns the address that frames are stored relative to when encoding a backtrace.
+#[allow(clippy::missing_const_for_fn)]
+fn program_base() -> u32 {
+    #[cfg(target_os = "vexos")]
+    {
+        unsafe extern "C" {
+            // Defined in https://github.com/rust-lang/rust/blob/master/compiler/rustc_target/src/spec/targets/armv7a_vex_v5_linker_script.ld.
+            static __user_ram_start: u8;
+        }
+
+        (&raw const __user_ram_start).addr() as u32
+    }
+
+    #[cfg(not(target_os = "vexos"))]
+    0
+}
+
+impl Backtrace {
+    /// Encodes the backtrace in a compact binary format, along with information about the program
+    /// that captured it.
+    ///
+    /// This is useful for sending backtraces over a radio link or saving them to a file, since
+    /// the encoded form is usually only a few bytes per frame. It can be read back on any
+    /// platform, including a computer, using [`Backtrace::decode`].
+    ///
+    /// # Format
+    ///
+    /// The encoding is versioned, so backtraces encoded by older versions of vexide can still be
+    /// decoded. Version 1 is laid out as follows, with all fixed-size integers in little-endian
+    /// order:
+    ///
+    /// | Field            | Size     | Description                                            |
+    /// |------------------|----------|--------------------------------------------------------|
+    /// | Magic            | 4 bytes  | The bytes `VXBT`.                                      |
+    /// | Version          | 1 byte   | The version of the encoding (1).                       |
+    /// | Code signature   | 16 bytes | The program's [`CodeSignature`] header.                |
+    /// | VEXos version    | 4 bytes  | The major, minor, build, and beta version numbers.     |
+    /// | Program base     | 4 bytes  | The address that the program was loaded at.            |
+    /// | Frame count      | varint   | The number of frames in the backtrace.                 |
+    /// | Frames           | varints  | Each frame's offset from the program base (zigzagged). |
+    ///
+    /// Varints are unsigned LEB128. Frame offsets are signed, since frames may be below the
+    /// program base, and are zigzag-encoded before being written.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        self.encode_for(code_signature(), system_version(), program_base())
+    }
+
+    /// Encodes the backtrace as if it were captured by a program with the given code signature,
+    /// VEXos version, and base address.
+    fn encode_for(&self, code_signature: CodeSignature, version: Version, base: u32) -> Vec<u8> {
+        // Every varint takes at most 10 bytes.
+        let mut bytes = vec![0; HEADER_SIZE + (self.frames.len() + 1) * MAX_VARINT_SIZE];
+        let len = encode_frames_for(&self.frames, code_signature, version, base, &mut bytes)
+            .expect("buffer is large enough for any backtrace");
+        bytes.truncate(len);
+        bytes
+    }
+
+    /// Encodes a backtrace with the given frames into `out` without allocating, returning the
+    /// number of bytes written.
+    ///
+    /// This writes the same format as [`Backtrace::encode`], and is intended for code that can't
+    /// rely on the heap, such as a CPU fault handler. [`None`] is returned if `out` is too small to
+    /// hold the encoded backtrace.
+    pub fn encode_frames_into(frames: &[*const ()], out: &mut [u8]) -> Option<usize> {
+        encode_frames_for(
+            frames,
+            code_signature(),
+            system_version(),
+            program_base(),
+            out,
+        )
+    }
+
+    /// Decodes a backtrace that was encoded with [`Backtrace::encode`].
+    ///
+    /// This doesn't depend on any VEX hardware, so it can be used by tools running on a computer
+    /// to read backtraces sent from a robot. Any bytes after the end of the backtrace are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`DecodeBacktraceError::InvalidMagic`] is returned if the data isn't an encoded backtrace.
+    /// - [`DecodeBacktraceError::UnsupportedVersion`] is returned if the backtrace was encoded with
+    ///   a newer version of the format.
+    /// - [`DecodeBacktraceError::InvalidCodeSignature`] is returned if the program's code signature
+    ///   is invalid.
+    /// - [`DecodeBacktraceError::Truncated`] is returned if the data ends partway through the
+    ///   backtrace.
+    /// - [`DecodeBacktraceError::InvalidVarint`] is returned if a varint is too large to have been
+    ///   written by [`Backtrace::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<DecodedBacktrace, DecodeBacktraceError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take::<4>()? != MAGIC {
+            return Err(DecodeBacktraceError::InvalidMagic);
+        }
+
+        let [version] = reader.take::<1>()?;
+        if version != VERSION {
+            return Err(DecodeBacktraceError::UnsupportedVersion(version));
+        }
+
+        let code_signature = CodeSignature::from_bytes(reader.take()?)
+            .ok_or(DecodeBacktraceError::InvalidCodeSignature)?;
+        let [major, minor, build, beta] = reader.take()?;
+        let program_base = u32::from_le_bytes(reader.take()?);
+
+        let count = reader.varint()?;
+        let mut frames = Vec::new();
+        for _ in 0..count {
+            let offset = unzigzag(reader.varint()?);
+            let address = (i64::from(program_base) + offset) as u32;
+            frames.push(address as usize as *const ());
+        }
+
+        Ok(DecodedBacktrace {
+            backtrace: Backtrace { frames },
+            code_signature,
+            os_version: Version {
+                major,
+                minor,
+                build,
+                beta,
+            },
+            program_base: program_base as usize,
+        })
+    }
+}
+
+/// A backtrace read from its binary encoding, along with information about the program that
+/// captured it.
+///
+/// This type is returned by [`Backtrace::decode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodedBacktrace {
+    /// The decoded backtrace.
+    pub backtrace: Backtrace,
+
+    /// The code signature of the program that captured the backtrace.
+    pub code_signature: CodeSignature,
+
+    /// The version of VEXos that the program was running on.
+    pub os_version: Version,
+
+    /// The address that the program was loaded at.
+    pub program_base: usize,
+}
+
+impl fmt::Display for DecodedBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "captured on VEXos {} (program base 0x{:x})",
+            self.os_version, self.program_base
+        )?;
+        write!(f, "{}", self.backtrace)
+    }
+}
+
+/// An error that can occur when decoding a backtrace with [`Backtrace::decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeBacktraceError {
+    /// The data doesn't start with the magic bytes of an encoded backtrace.
+    InvalidMagic,
+
+    /// The backtrace was encoded with a version of the format that isn't supported.
+    UnsupportedVersion(u8),
+
+    /// The code signature of the program that captured the backtrace is invalid.
+    InvalidCodeSignature,
+
+    /// The data ended before the end of the backtrace.
+    Truncated,
+
+    /// The data contains a varint that is too large to have been written by [`Backtrace::encode`].
+    InvalidVarint,
+}
+
+impl fmt::Display for DecodeBacktraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "data is not an encoded backtrace"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported backtrace encoding version {version}")
+            }
+            Self::InvalidCodeSignature => write!(f, "backtrace has an invalid code signature"),
+            Self::Truncated => write!(f, "encoded backtrace is truncated"),
+            Self::InvalidVarint => write!(f, "encoded backtrace contains an invalid varint"),
+        }
+    }
+}
+
+impl Error for DecodeBacktraceError {}
+
+const fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+const fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+fn encode_frames_for(
+    frames: &[*const ()],
+    code_signature: CodeSignature,
+    version: Version,
+    base: u32,
+    out: &mut [u8],
+) -> Option<usize> {
+    let mut writer = Writer { bytes: out, len: 0 };
+    writer.write(&MAGIC)?;
+    writer.write(&[VERSION])?;
+    writer.write(&code_signature.to_bytes())?;
+    writer.write(&[version.major, version.minor, version.build, version.beta])?;
+    writer.write(&base.to_le_bytes())?;
+
+    writer.varint(frames.len() as u64)?;
+    for frame in frames {
+        let offset = i64::from(frame.addr() as u32) - i64::from(base);
+        writer.varint(zigzag(offset))?;
+    }
+
+    Some(writer.len)
+}
+
+/// Writes to a fixed-size buffer, so that backtraces can be encoded without allocating.
+struct Writer<'a> {
+    bytes: &'a mut [u8],
+    len: usize,
+}
+
+impl Writer<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Option<()> {
+        let end = self.len.checked_add(bytes.len())?;
+        self.bytes.get_mut(self.len..end)?.copy_from_slice(bytes);
+        self.len = end;
+        Some(())
+    }
+
+    fn varint(&mut self, mut n: u64) -> Option<()> {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+
+            if n == 0 {
+                return self.write(&[byte]);
+            }
+            self.write(&[byte | 0x80])?;
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeBacktraceError> {
+        let (taken, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .ok_or(DecodeBacktraceError::Truncated)?;
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeBacktraceError> {
+        let mut n = 0;
+        for shift in (0..64).step_by(7) {
+            let [byte] = self.take::<1>()?;
+
+            // Only the lowest bit of the tenth byte fits in a `u64`.
+            if shift == 63 && byte > 1 {
+                return Err(DecodeBacktraceError::InvalidVarint);
+            }
+            n |= u64::from(byte & 0x7f) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+
+        // A varint this long can't have come from `Writer::varint`.
+        Err(DecodeBacktraceError::InvalidVarint)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::program::{ProgramOptions, ProgramOwner, ProgramType};
+
+    const BASE: u32 = 0x0380_0000;
+
+    const SIGNATURE: CodeSignature = CodeSignature::new(
+        ProgramType::User,
+        ProgramOwner::Partner,
+        ProgramOptions::KILL_TASKS_ON_EXIT,
+    );
+
+    const OS_VERSION: Version = Version {
+        major: 1,
+        minor: 1,
+        build: 5,
+        beta: 0,
+    };
+
+    fn backtrace(frames: &[u32]) -> Backtrace {
+        Backtrace {
+            frames: frames
+                .iter()
+                .map(|&frame| frame as usize as *const ())
+                .collect(),
+        }
+    }
+
+    fn encode(frames: &[u32]) -> Vec<u8> {
+        backtrace(frames).encode_for(SIGNATURE, OS_VERSION, BASE)
+    }
+
+    #[test]
+    fn round_trip() {
+        // Includes frames below the program base, which have negative offsets.
+        let frames = [BASE + 0x217b, BASE + 0x209b, BASE, BASE - 4, 0, u32::MAX];
+        let decoded = Backtrace::decode(&encode(&frames)).unwrap();
+
+        assert_eq!(decoded.backtrace, backtrace(&frames));
+        assert_eq!(decoded.code_signature, SIGNATURE);
+        assert_eq!(decoded.os_version, OS_VERSION);
+        assert_eq!(decoded.program_base, BASE as usize);
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        let decoded = Backtrace::decode(&encode(&[])).unwrap();
+        assert!(decoded.backtrace.frames().is_empty());
+    }
+
+    #[test]
+    fn encodes_into_fixed_buffers() {
+        let frames = [BASE + 0x217b, BASE - 4, u32::MAX];
+        let expected = encode(&frames);
+        let frames = backtrace(&frames).frames;
+
+        let mut buffer = [0; 64];
+        let len = encode_frames_for(&frames, SIGNATURE, OS_VERSION, BASE, &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], expected);
+
+        let mut buffer = vec![0; expected.len() - 1];
+        assert_eq!(
+            encode_frames_for(&frames, SIGNATURE, OS_VERSION, BASE, &mut buffer),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_trailing_bytes() {
+        let frames = [BASE + 0x100];
+        let mut bytes = encode(&frames);
+        bytes.extend_from_slice(&[0xff; 4]);
+
+        assert_eq!(
+            Backtrace::decode(&bytes).unwrap().backtrace,
+            backtrace(&frames)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let bytes = encode(&[BASE + 0x217b, BASE - 0x1000]);
+
+        for len in 0..bytes.len() {
+            assert_eq!(
+                Backtrace::decode(&bytes[..len]),
+                Err(DecodeBacktraceError::Truncated),
+                "decoded {len} of {} bytes",
+                bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = encode(&[BASE]);
+        bytes[..4].copy_from_slice(b"VXCR");
+
+        assert_eq!(
+            Backtrace::decode(&bytes),
+            Err(DecodeBacktraceError::InvalidMagic)
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut bytes = encode(&[BASE]);
+        bytes[4] = VERSION + 1;
+
+        assert_eq!(
+            Backtrace::decode(&bytes),
+            Err(DecodeBacktraceError::UnsupportedVersion(VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_code_signature() {
+        let mut bytes = encode(&[BASE]);
+        bytes[5] ^= 0xff;
+
+        assert_eq!(
+            Backtrace::decode(&bytes),
+            Err(DecodeBacktraceError::InvalidCodeSignature)
+        );
+    }
+
+    #[test]
+    fn rejects_overlong_varints() {
+        // The header of an encoded backtrace, without its frame count.
+        let header = &encode(&[])[..29];
+
+        // Eleven bytes is longer than any `u64`.
+        let mut bytes = header.to_vec();
+        bytes.extend_from_slice(&[0x80; 10]);
+        bytes.push(0x00);
+        assert_eq!(
+            Backtrace::decode(&bytes),
+            Err(DecodeBacktraceError::InvalidVarint)
+        );
+
+        // Ten bytes, but with more than one bit set in the last byte.
+        let mut bytes = header.to_vec();
+        bytes.extend_from_slice(&[0xff; 9]);
+        bytes.push(0x02);
+        assert_eq!(
+            Backtrace::decode(&bytes),
+            Err(DecodeBacktraceError::InvalidVarint)
+        );
+    }
+
+    #[test]
+    fn varint_round_trip() {
+        for n in [
+            0,
+            1,
+            0x7f,
+            0x80,
+            0x3fff,
+            0x4000,
+            u64::from(u32::MAX),
+            u64::MAX,
+        ] {
+            let mut bytes = [0; MAX_VARINT_SIZE];
+            let mut writer = Writer {
+                bytes: &mut bytes,
+                len: 0,
+            };
+            writer.varint(n).unwrap();
+            let len = writer.len;
+
+            let mut reader = Reader {
+                bytes: &bytes[..len],
+            };
+            assert_eq!(reader.varint(), Ok(n));
+            assert!(reader.bytes.is_empty());
+        }
+    }
+
+    #[test]
+    fn zigzag_round_trip() {
+        for n in [
+            0,
+            1,
+            -1,
+            i64::from(i32::MIN),
+            i64::from(u32::MAX),
+            i64::MIN,
+            i64::MAX,
+        ] {
+            assert_eq!(unzigzag(zigzag(n)), n);
+        }
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+    }
+
+    #[test]
+    fn code_signature_round_trip() {
+        assert_eq!(
+            CodeSignature::from_bytes(SIGNATURE.to_bytes()),
+            Some(SIGNATURE)
+        );
+        assert_eq!(CodeSignature::from_bytes([0; 16]), None);
+
+        let mut bad_owner = SIGNATURE.to_bytes();
+        bad_owner[8] = 3;
+        assert_eq!(CodeSignature::from_bytes(bad_owner), None);
+    }
+}
@@ -10,6 +10,16 @@
 //!
 //! Additionally, backtraces will be unsupported if vexide is compiled without the `backtrace`
 //! feature.
+//!
+//! # Symbolication
+//!
+//! With the `symbols` feature, a [`SymbolTable`] can be embedded in the program after it's linked
+//! so that backtraces show function names on the brain itself, rather than only raw addresses.
+//!
+//! # Serialization
+//!
+//! Backtraces can be converted to a compact binary format with [`Backtrace::encode`] to be sent
+//! elsewhere or saved for later, then read back on any platform with [`Backtrace::decode`].
 
 use alloc::vec::Vec;
 use core::fmt::Display;
@@ -17,6 +27,12 @@ use core::fmt::Display;
 #[cfg(all(target_os = "vexos", feature = "backtrace"))]
 use vex_libunwind::{UnwindContext, UnwindCursor, UnwindError, registers};
 
+mod encoding;
+mod symbols;
+
+pub use encoding::{DecodeBacktraceError, DecodedBacktrace};
+pub use symbols::{Symbol, SymbolTable, symbolize};
+
 /// A captured stack backtrace.
 ///
 /// This type stores the backtrace of a captured stack at a certain point in time. The backtrace is
@@ -50,6 +66,9 @@ use vex_libunwind::{UnwindContext, UnwindCursor, UnwindError, registers};
 ///
 /// main at /path/to/project/src/main.rs:21:9
 /// ```
+///
+/// If the program contains a [`SymbolTable`], function names are looked up on the brain and
+/// included when the backtrace is printed.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Backtrace {
     /// The instruction pointers of each frame in the backtrace.
@@ -122,16 +141,35 @@ impl Backtrace {
     }
 }
 
+impl FromIterator<*const ()> for Backtrace {
+    /// Creates a backtrace from the instruction pointer of each frame, such as frames unwound from
+    /// the context of a CPU fault.
+    fn from_iter<I: IntoIterator<Item = *const ()>>(frames: I) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+        }
+    }
+}
+
 impl Display for Backtrace {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let symbols = SymbolTable::current();
+
         writeln!(f, "stack backtrace:")?;
         for (i, frame) in self.frames.iter().enumerate() {
-            writeln!(f, "{i:>3}: 0x{:x}", *frame as usize)?;
+            write!(f, "{i:>3}: 0x{:x}", *frame as usize)?;
+            if let Some(symbol) = symbols.and_then(|table| table.lookup(*frame as usize)) {
+                write!(f, " - {symbol}")?;
+            }
+            writeln!(f)?;
+        }
+
+        if symbols.is_none() {
+            write!(
+                f,
+                "note: Use a symbolizer to convert stack frames to human-readable function names."
+            )?;
         }
-        write!(
-            f,
-            "note: Use a symbolizer to convert stack frames to human-readable function names."
-        )?;
         Ok(())
     }
 }
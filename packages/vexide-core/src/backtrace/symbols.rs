@@ -0,0 +1,406 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// The size of the link section reserved for the program's symbol table.
+#[cfg(all(target_os = "vexos", feature = "symbols"))]
+const RESERVED_SIZE: usize = 64 * 1024;
+
+/// Space for the symbol table, which is filled in after the program is linked.
+///
+/// This is never read directly, since the compiler would assume that it only contains zeroes.
+/// Instead, the table is found using the bounds of its link section.
+#[cfg(all(target_os = "vexos", feature = "symbols"))]
+#[used]
+#[unsafe(link_section = ".vexide_symbols")]
+static RESERVED: [u8; RESERVED_SIZE] = [0; RESERVED_SIZE];
+
+/// The symbol table installed with [`SymbolTable::install`], or null if there isn't one.
+static CURRENT: AtomicPtr<SymbolTable<'static>> = AtomicPtr::new(ptr::null_mut());
+
+/// A function in the program's symbol table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol<'a> {
+    name: &'a str,
+    address: usize,
+    offset: usize,
+}
+
+impl<'a> Symbol<'a> {
+    /// Returns the name of the function.
+    #[must_use]
+    pub const fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the address of the start of the function.
+    #[must_use]
+    pub const fn address(&self) -> usize {
+        self.address
+    }
+
+    /// Returns how far into the function the looked up address was, in bytes.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for Symbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+0x{:x}", self.name, self.offset)
+    }
+}
+
+/// A table mapping function addresses to their names, used to symbolicate backtraces without a
+/// computer.
+///
+/// When the `symbols` feature is enabled, space for a symbol table is reserved in the
+/// `.vexide_symbols` link section of the program. Function addresses are only known once the
+/// program has been linked, so the table is filled in afterwards by the `vexide-symbols` tool,
+/// which reads the functions from the program's ELF file and writes a table created with
+/// [`SymbolTable::encode`] into the section:
+///
+/// ```sh
+/// cargo install vexide-symbols
+/// vexide-symbols target/armv7a-vex-v5/release/my-program
+/// ```
+///
+/// This must happen before the ELF file is converted to the binary that's uploaded to the brain.
+/// If it hasn't been done, backtraces are printed as raw addresses like usual.
+///
+/// A table can also be loaded by the program itself, such as from a file on the SD card, and
+/// made available to vexide with [`SymbolTable::install`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use vexide::{backtrace::SymbolTable, prelude::*};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     // Created from this program's ELF file after it was built.
+///     if let Ok(bytes) = std::fs::read("symbols.bin") {
+///         let bytes = Vec::leak(bytes);
+///         if let Some(table) = SymbolTable::parse(bytes) {
+///             table.install();
+///         }
+///     }
+/// }
+/// ```
+///
+/// # Format
+///
+/// All integers are little-endian `u32`s. The table begins with a header containing the magic
+/// bytes `VXSY`, the format version (currently 1), the number of functions, and the length of the
+/// name data. The header is followed by an entry for each function, sorted by address, containing
+/// its address, its size in bytes, and the offset and length of its name. The UTF-8 names of every
+/// function come last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolTable<'a> {
+    entries: &'a [u8],
+    names: &'a [u8],
+}
+
+impl<'a> SymbolTable<'a> {
+    /// The magic bytes at the start of every symbol table.
+    pub const MAGIC: [u8; 4] = *b"VXSY";
+
+    /// The version of the symbol table format that this type reads and writes.
+    pub const VERSION: u32 = 1;
+
+    const HEADER_SIZE: usize = 16;
+    const ENTRY_SIZE: usize = 16;
+
+    /// Returns the symbol table used to symbolicate backtraces, if there is one.
+    ///
+    /// This is the table passed to [`SymbolTable::install`] if one has been installed, and
+    /// otherwise the table embedded in the program's `.vexide_symbols` link section. There is no
+    /// embedded table if the `symbols` feature is disabled, when not running on a VEX brain, or if
+    /// the section was never filled in after linking.
+    #[must_use]
+    pub fn current() -> Option<SymbolTable<'static>> {
+        let table = CURRENT.load(Ordering::Acquire);
+        // SAFETY: `CURRENT` is either null or points to a table leaked by `install`.
+        if let Some(table) = unsafe { table.as_ref() } {
+            return Some(*table);
+        }
+
+        Self::embedded()
+    }
+
+    /// Returns the symbol table in the program's `.vexide_symbols` link section, if it was filled
+    /// in.
+    #[allow(clippy::missing_const_for_fn)]
+    fn embedded() -> Option<SymbolTable<'static>> {
+        #[cfg(all(target_os = "vexos", feature = "symbols"))]
+        {
+            unsafe extern "C" {
+                // Defined in vexide-startup's linker script.
+                static __vexide_symbols_start: u8;
+                static __vexide_symbols_end: u8;
+            }
+
+            let start = &raw const __vexide_symbols_start;
+            let end = &raw const __vexide_symbols_end;
+
+            // Make sure that the reserved space is linked into the program.
+            core::hint::black_box(&raw const RESERVED);
+
+            let bytes =
+                unsafe { core::slice::from_raw_parts(start, end.offset_from_unsigned(start)) };
+            SymbolTable::parse(bytes)
+        }
+
+        #[cfg(not(all(target_os = "vexos", feature = "symbols")))]
+        None
+    }
+
+    /// Reads a symbol table from its encoded form, returning [`None`] if it's invalid.
+    ///
+    /// Any bytes after the end of the table are ignored.
+    #[must_use]
+    pub fn parse(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.get(..4)? != Self::MAGIC || read_u32(bytes, 4)? != Self::VERSION {
+            return None;
+        }
+
+        let count = read_u32(bytes, 8)? as usize;
+        let names_len = read_u32(bytes, 12)? as usize;
+
+        let entries_end = Self::HEADER_SIZE.checked_add(count.checked_mul(Self::ENTRY_SIZE)?)?;
+        let entries = bytes.get(Self::HEADER_SIZE..entries_end)?;
+        let names = bytes.get(entries_end..entries_end.checked_add(names_len)?)?;
+
+        Some(Self { entries, names })
+    }
+
+    /// Returns the number of functions in the table.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len() / Self::ENTRY_SIZE
+    }
+
+    /// Returns `true` if the table doesn't contain any functions.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the address of the entry at `index`.
+    fn address(&self, index: usize) -> Option<usize> {
+        read_u32(self.entries, index * Self::ENTRY_SIZE).map(|address| address as usize)
+    }
+
+    /// Returns the address, size, and name of the entry at `index`.
+    fn entry(&self, index: usize) -> Option<(usize, usize, &'a str)> {
+        let offset = index * Self::ENTRY_SIZE;
+        let address = self.address(index)?;
+        let size = read_u32(self.entries, offset + 4)? as usize;
+        let name_offset = read_u32(self.entries, offset + 8)? as usize;
+        let name_len = read_u32(self.entries, offset + 12)? as usize;
+
+        let name = self
+            .names
+            .get(name_offset..name_offset.checked_add(name_len)?)?;
+        Some((address, size, core::str::from_utf8(name).ok()?))
+    }
+
+    /// Finds the function containing `address`, if there is one in the table.
+    #[must_use]
+    pub fn lookup(&self, address: usize) -> Option<Symbol<'a>> {
+        // Find the last function that starts at or before the address.
+        let mut low = 0;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.address(mid)? <= address {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        let (start, size, name) = self.entry(low.checked_sub(1)?)?;
+        let offset = address - start;
+
+        (offset < size).then_some(Symbol {
+            name,
+            address: start,
+            offset,
+        })
+    }
+
+    /// Encodes a symbol table containing the given functions, each of which is an address, a size
+    /// in bytes, and a name.
+    ///
+    /// This is intended to be used by build tools running on a computer, which can read the
+    /// functions from the program's ELF file. The functions don't need to be sorted.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the table would be larger than 4 GiB.
+    #[must_use]
+    pub fn encode<'n>(functions: impl IntoIterator<Item = (u32, u32, &'n str)>) -> Vec<u8> {
+        let mut functions = functions.into_iter().collect::<Vec<_>>();
+        functions.sort_unstable_by_key(|&(address, ..)| address);
+
+        let names_len = functions.iter().map(|(.., name)| name.len()).sum::<usize>();
+        let mut bytes =
+            Vec::with_capacity(Self::HEADER_SIZE + functions.len() * Self::ENTRY_SIZE + names_len);
+
+        let to_u32 = |n: usize| u32::try_from(n).expect("symbol table is too large");
+
+        bytes.extend_from_slice(&Self::MAGIC);
+        bytes.extend_from_slice(&Self::VERSION.to_le_bytes());
+        bytes.extend_from_slice(&to_u32(functions.len()).to_le_bytes());
+        bytes.extend_from_slice(&to_u32(names_len).to_le_bytes());
+
+        let mut name_offset = 0;
+        for &(address, size, name) in &functions {
+            bytes.extend_from_slice(&address.to_le_bytes());
+            bytes.extend_from_slice(&size.to_le_bytes());
+            bytes.extend_from_slice(&to_u32(name_offset).to_le_bytes());
+            bytes.extend_from_slice(&to_u32(name.len()).to_le_bytes());
+            name_offset += name.len();
+        }
+
+        for (.., name) in functions {
+            bytes.extend_from_slice(name.as_bytes());
+        }
+
+        bytes
+    }
+}
+
+impl SymbolTable<'static> {
+    /// Makes this the symbol table used to symbolicate backtraces, replacing any table that was
+    /// installed before or embedded in the program.
+    ///
+    /// Once installed, function names are shown by [`Backtrace`](super::Backtrace)'s `Display`
+    /// implementation, the panic hook, and the abort handler. The table's bytes must live for the
+    /// rest of the program, such as by being leaked or stored in a `static`.
+    pub fn install(self) {
+        // The previous table is leaked, since a backtrace may still be reading it.
+        CURRENT.store(Box::into_raw(Box::new(self)), Ordering::Release);
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Looks up the function containing `address` in the [current](SymbolTable::current) symbol table.
+///
+/// See [`SymbolTable`] for more information about how the table is created.
+#[must_use]
+pub fn symbolize(address: usize) -> Option<Symbol<'static>> {
+    SymbolTable::current()?.lookup(address)
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    fn encode_test_table() -> Vec<u8> {
+        SymbolTable::encode([
+            (0x300, 0x40, "third"),
+            (0x100, 0x20, "first"),
+            (0x200, 0x10, "second"),
+        ])
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = encode_test_table();
+        let table = SymbolTable::parse(&bytes).unwrap();
+
+        assert_eq!(table.len(), 3);
+        assert_eq!(
+            table.lookup(0x100),
+            Some(Symbol {
+                name: "first",
+                address: 0x100,
+                offset: 0,
+            })
+        );
+        assert_eq!(table.lookup(0x20f).unwrap().to_string(), "second+0xf");
+        assert_eq!(table.lookup(0x33f).unwrap().name(), "third");
+    }
+
+    #[test]
+    fn lookup_outside_functions() {
+        let bytes = encode_test_table();
+        let table = SymbolTable::parse(&bytes).unwrap();
+
+        assert_eq!(table.lookup(0), None);
+        assert_eq!(table.lookup(0xff), None);
+        // The address just past the end of a function isn't part of it.
+        assert_eq!(table.lookup(0x120), None);
+        assert_eq!(table.lookup(0x340), None);
+    }
+
+    #[test]
+    fn empty_table() {
+        let bytes = SymbolTable::encode([]);
+        let table = SymbolTable::parse(&bytes).unwrap();
+
+        assert!(table.is_empty());
+        assert_eq!(table.lookup(0x100), None);
+    }
+
+    #[test]
+    fn rejects_invalid_tables() {
+        let bytes = encode_test_table();
+
+        assert_eq!(SymbolTable::parse(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(SymbolTable::parse(&bytes[..8]), None);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(SymbolTable::parse(&bad_magic), None);
+
+        let mut bad_version = bytes;
+        bad_version[4] = 2;
+        assert_eq!(SymbolTable::parse(&bad_version), None);
+    }
+
+    #[test]
+    fn invalid_utf8_names_are_skipped() {
+        let mut bytes = SymbolTable::encode([(0x100, 0x20, "ok"), (0x200, 0x20, "no")]);
+        let len = bytes.len();
+        bytes[len - 2..].copy_from_slice(&[0xff, 0xfe]);
+        let table = SymbolTable::parse(&bytes).unwrap();
+
+        assert_eq!(table.lookup(0x100).unwrap().name(), "ok");
+        assert_eq!(table.lookup(0x200), None);
+    }
+
+    #[test]
+    fn installed_table_is_current() {
+        /// Uninstalls the test's table even if an assertion fails, so that other tests don't print
+        /// symbolicated backtraces.
+        struct Restore(*mut SymbolTable<'static>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.store(self.0, Ordering::Release);
+            }
+        }
+
+        let _restore = Restore(CURRENT.load(Ordering::Acquire));
+
+        let bytes = SymbolTable::encode([(0x100, 0x20, "installed")]).leak();
+        SymbolTable::parse(bytes).unwrap().install();
+
+        assert_eq!(symbolize(0x110).unwrap().name(), "installed");
+        assert_eq!(SymbolTable::current().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,231 @@
+use alloc::{collections::VecDeque, rc::Rc, vec::Vec};
+use core::{cell::RefCell, fmt, time::Duration};
+
+use super::CompetitionMode;
+use crate::time::clock_uptime;
+
+/// A change from one competition mode to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// The mode that the robot was in before the transition.
+    pub from: CompetitionMode,
+
+    /// The mode that the robot was in after the transition.
+    pub to: CompetitionMode,
+
+    /// The time since the user program started at which the transition happened.
+    ///
+    /// In simulations, this is measured by the runtime's clock, so it respects a paused clock.
+    pub timestamp: Duration,
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>4}.{:03}s  {:?} -> {:?}",
+            self.timestamp.as_secs(),
+            self.timestamp.subsec_millis(),
+            self.from,
+            self.to
+        )
+    }
+}
+
+/// A record of the most recent mode transitions made by a [`CompetitionRuntime`].
+///
+/// Only the last [`capacity`](Self::capacity) transitions are kept, so the history can be left
+/// recording for the lifetime of the program. This type is a cheaply cloneable handle: clones
+/// refer to the same history, so a clone can be kept in your robot struct and printed after a
+/// match while the runtime continues to record into it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use vexide::{competition::TransitionHistory, prelude::*};
+///
+/// struct Robot {
+///     history: TransitionHistory,
+/// }
+///
+/// impl Compete for Robot {
+///     async fn disconnected(&mut self) {
+///         // Print every transition from the match that just ended.
+///         println!("{}", self.history);
+///     }
+/// }
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     let robot = Robot {
+///         history: TransitionHistory::default(),
+///     };
+///     let history = robot.history.clone();
+///
+///     robot.compete().with_history(history).await;
+/// }
+/// ```
+///
+/// [`CompetitionRuntime`]: super::CompetitionRuntime
+#[derive(Clone)]
+pub struct TransitionHistory {
+    inner: Rc<RefCell<VecDeque<Transition>>>,
+    capacity: usize,
+}
+
+impl TransitionHistory {
+    /// The number of transitions kept by [`TransitionHistory::default`].
+    pub const DEFAULT_CAPACITY: usize = 32;
+
+    /// Creates an empty history that keeps up to `capacity` transitions.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `capacity` is zero.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a transition history must have a nonzero capacity"
+        );
+
+        Self {
+            inner: Rc::new(RefCell::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of transitions that the history keeps.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of transitions currently in the history.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.borrow().len()
+    }
+
+    /// Returns `true` if no transitions have been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().is_empty()
+    }
+
+    /// Returns the most recent transition, if there is one.
+    #[must_use]
+    pub fn last(&self) -> Option<Transition> {
+        self.inner.borrow().back().copied()
+    }
+
+    /// Returns every transition in the history, from oldest to newest.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<Transition> {
+        self.inner.borrow().iter().copied().collect()
+    }
+
+    /// Removes every transition from the history.
+    pub fn clear(&self) {
+        self.inner.borrow_mut().clear();
+    }
+
+    /// Records a transition that is happening now, discarding the oldest one if the history is
+    /// full.
+    pub(crate) fn record(&self, from: CompetitionMode, to: CompetitionMode) {
+        let mut transitions = self.inner.borrow_mut();
+        if transitions.len() == self.capacity {
+            transitions.pop_front();
+        }
+
+        transitions.push_back(Transition {
+            from,
+            to,
+            timestamp: clock_uptime(),
+        });
+    }
+}
+
+impl Default for TransitionHistory {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl fmt::Debug for TransitionHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.inner.borrow().iter()).finish()
+    }
+}
+
+/// Prints one transition per line, from oldest to newest.
+impl fmt::Display for TransitionHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, transition) in self.inner.borrow().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{transition}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    #[test]
+    fn evicts_oldest_transitions() {
+        let history = TransitionHistory::new(2);
+
+        history.record(CompetitionMode::Disabled, CompetitionMode::Autonomous);
+        history.record(CompetitionMode::Autonomous, CompetitionMode::Disabled);
+        history.record(CompetitionMode::Disabled, CompetitionMode::Driver);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history
+                .to_vec()
+                .iter()
+                .map(|transition| (transition.from, transition.to))
+                .collect::<Vec<_>>(),
+            [
+                (CompetitionMode::Autonomous, CompetitionMode::Disabled),
+                (CompetitionMode::Disabled, CompetitionMode::Driver),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "sim")]
+    fn timestamps_follow_paused_clock() {
+        use crate::time;
+
+        time::pause();
+        let history = TransitionHistory::default();
+
+        history.record(CompetitionMode::Disabled, CompetitionMode::Autonomous);
+        time::advance(Duration::from_secs(15));
+        history.record(CompetitionMode::Autonomous, CompetitionMode::Disabled);
+
+        let transitions = history.to_vec();
+        assert_eq!(
+            transitions[1]
+                .timestamp
+                .saturating_sub(transitions[0].timestamp),
+            Duration::from_secs(15)
+        );
+
+        time::resume();
+    }
+
+    #[test]
+    #[should_panic = "nonzero capacity"]
+    fn zero_capacity_panics() {
+        _ = TransitionHistory::new(0);
+    }
+}
@@ -47,22 +47,40 @@
 //! functions for reading information about the competition environment, such as the current match
 //! mode, match control hardware, and whether the robot is enabled or disabled. This is provided by
 //! the [`is_connected`], [`system`], [`mode`], and [`status`] functions.
+//!
+//! # Testing
+//!
+//! With the `test-util` feature, the `sim` module can be used to simulate competition control
+//! in tests, allowing a [`Compete`] implementation to be run through a scripted match.
 
-use alloc::boxed::Box;
+mod history;
+mod selector;
+#[cfg(feature = "sim")]
+pub mod sim;
+
+use alloc::{boxed::Box, collections::BTreeMap, rc::Rc};
 use core::{
-    cell::UnsafeCell,
+    any::Any,
+    cell::{Cell, RefCell, UnsafeCell},
+    fmt,
     future::{Future, IntoFuture},
     marker::{PhantomData, PhantomPinned},
     ops::ControlFlow,
     pin::Pin,
     task::{self, Poll},
+    time::Duration,
 };
 
 use bitflags::bitflags;
 use futures_core::Stream;
+pub use history::{Transition, TransitionHistory};
 use pin_project::pin_project;
+use selector::SharedUi;
+pub use selector::{AutonSelector, SelectorInput, SelectorUi};
 use vex_sdk::vexCompetitionStatus;
 
+use crate::time::clock_uptime;
+
 bitflags! {
     /// The raw status bits returned by [`vex_sdk::vexCompetitionStatus`].
     ///
@@ -215,6 +233,11 @@ impl CompetitionStatus {
 /// See [`CompetitionStatus`] for usage and examples.
 #[must_use]
 pub fn status() -> CompetitionStatus {
+    #[cfg(feature = "sim")]
+    if let Some(status) = sim::status() {
+        return status;
+    }
+
     CompetitionStatus::from_bits_retain(unsafe { vexCompetitionStatus() })
 }
 
@@ -282,8 +305,17 @@ impl Stream for CompetitionUpdates {
     fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
         let current = status();
 
+        // Simulated statuses only change when the simulation says so, so there's no need to keep
+        // checking for updates.
+        #[cfg(feature = "sim")]
+        let registered = sim::register_waker(cx.waker());
+        #[cfg(not(feature = "sim"))]
+        let registered = false;
+
         // TODO: This should probably be done on a timer in the reactor.
-        cx.waker().wake_by_ref();
+        if !registered {
+            cx.waker().wake_by_ref();
+        }
 
         if self.last_status == Some(current) {
             Poll::Pending
@@ -312,6 +344,325 @@ pub const fn updates() -> CompetitionUpdates {
     CompetitionUpdates { last_status: None }
 }
 
+/// The standard length of each period of a match.
+///
+/// This is used by [`MatchClock`] to calculate how much time is left in the current period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchFormat {
+    /// The length of the autonomous period.
+    pub autonomous: Duration,
+
+    /// The length of the driver control period.
+    pub driver: Duration,
+}
+
+impl MatchFormat {
+    /// The format of a VEX V5 Robotics Competition match: a 15 second autonomous period followed by
+    /// a 1 minute 45 second driver control period.
+    pub const VRC: Self = Self {
+        autonomous: Duration::from_secs(15),
+        driver: Duration::from_secs(105),
+    };
+
+    /// The format of a VEX U Robotics Competition match: a 45 second autonomous period followed by
+    /// a 1 minute 15 second driver control period.
+    pub const VEXU: Self = Self {
+        autonomous: Duration::from_secs(45),
+        driver: Duration::from_secs(75),
+    };
+
+    /// The format of a skills run, where both Programming Skills (autonomous) and Driving Skills
+    /// (driver control) last for 1 minute.
+    pub const SKILLS: Self = Self {
+        autonomous: Duration::from_mins(1),
+        driver: Duration::from_mins(1),
+    };
+
+    /// Returns the length of the period for the given competition mode, or [`None`] if the mode
+    /// doesn't have a fixed length.
+    #[must_use]
+    pub const fn duration(&self, mode: CompetitionMode) -> Option<Duration> {
+        match mode {
+            CompetitionMode::Autonomous => Some(self.autonomous),
+            CompetitionMode::Driver => Some(self.driver),
+            CompetitionMode::Disabled => None,
+        }
+    }
+}
+
+/// A timer that tracks how long the robot has been in the current competition mode.
+///
+/// VEXos reports which mode the robot is in, but not when the mode started. A `MatchClock` records
+/// the time of each mode transition it sees, and uses a [`MatchFormat`] to work out how much time
+/// is left in the current period.
+///
+/// The clock checks the competition status whenever it is queried, so a transition is recorded
+/// the first time the clock is used after it happens. For accurate timings, create the clock
+/// before the match starts and query it regularly (for instance, from a control loop), or pass it
+/// every value from [`updates`] using [`MatchClock::update`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{
+///     competition::{MatchClock, MatchFormat},
+///     prelude::*,
+/// };
+///
+/// struct Robot {
+///     clock: MatchClock,
+/// }
+///
+/// impl Compete for Robot {
+///     async fn autonomous(&mut self) {
+///         loop {
+///             if self
+///                 .clock
+///                 .remaining()
+///                 .is_some_and(|remaining| remaining < Duration::from_secs(3))
+///             {
+///                 println!("Time to park!");
+///                 break;
+///             }
+///
+///             // ...
+///             sleep(Duration::from_millis(10)).await;
+///         }
+///     }
+/// }
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     Robot {
+///         clock: MatchClock::new(MatchFormat::VRC),
+///     }
+///     .compete()
+///     .await;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MatchClock {
+    format: MatchFormat,
+    status: Cell<CompetitionStatus>,
+    mode_started: Cell<Duration>,
+}
+
+impl MatchClock {
+    /// Creates a new clock for matches in the given format, starting from the current mode.
+    ///
+    /// Since the clock can't know when the current mode actually started, the elapsed time in the
+    /// current mode is counted from when the clock is created.
+    #[must_use]
+    pub fn new(format: MatchFormat) -> Self {
+        Self {
+            format,
+            status: Cell::new(status()),
+            mode_started: Cell::new(clock_uptime()),
+        }
+    }
+
+    /// Returns the match format that the clock was created with.
+    #[must_use]
+    pub const fn format(&self) -> MatchFormat {
+        self.format
+    }
+
+    /// Records a new competition status, starting a new period if the mode or connection state has
+    /// changed.
+    ///
+    /// This doesn't need to be called manually, since the clock checks the current status whenever
+    /// it is queried. It is useful for recording transitions as soon as they are received from
+    /// [`updates`].
+    pub fn update(&self, status: CompetitionStatus) {
+        let previous = self.status.replace(status);
+
+        if previous.mode() != status.mode() || previous.is_connected() != status.is_connected() {
+            self.mode_started.set(clock_uptime());
+        }
+    }
+
+    /// Returns the current competition mode.
+    #[must_use]
+    pub fn mode(&self) -> CompetitionMode {
+        self.update(status());
+        self.status.get().mode()
+    }
+
+    /// Returns how long the robot has been in the current competition mode.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.update(status());
+        clock_uptime().saturating_sub(self.mode_started.get())
+    }
+
+    /// Returns how much time is left in the current period of the match, or [`None`] if the robot
+    /// is disabled.
+    ///
+    /// Once the standard length of the period has passed, this returns [`Duration::ZERO`].
+    #[must_use]
+    pub fn remaining(&self) -> Option<Duration> {
+        let elapsed = self.elapsed();
+        let duration = self.format.duration(self.status.get().mode())?;
+
+        Some(duration.saturating_sub(elapsed))
+    }
+}
+
+std::thread_local! {
+    /// The scope of the competition mode currently being polled on this thread, if any.
+    static CURRENT_SCOPE: RefCell<Option<ModeScope>> = const { RefCell::new(None) };
+}
+
+struct ScopeInner {
+    ended: Cell<bool>,
+    values: RefCell<BTreeMap<BindingKey, Box<dyn Any>>>,
+    next_key: Cell<u64>,
+}
+
+/// A handle to a value [bound](ModeScope::bind) to a [`ModeScope`], which can be used to
+/// [unbind](ModeScope::unbind) it before the scope ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BindingKey(u64);
+
+/// The lifetime of a single phase of a [`CompetitionRuntime`].
+///
+/// Each time the competition runtime switches to a different mode (or runs its connected and
+/// disconnected tasks), it starts a new scope. Values [bound](ModeScope::bind) to a scope are
+/// dropped as soon as the runtime leaves that phase, even if the mode's task has already finished.
+///
+/// This is mainly used to make sure that background tasks spawned by a mode don't keep running in
+/// the next one, such as an intake task from autonomous that would otherwise keep spinning during
+/// driver control. Tasks can be tied to the current mode using `vexide::task::spawn_scoped`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use vexide::{prelude::*, task::spawn_scoped};
+///
+/// struct Robot {}
+///
+/// impl Compete for Robot {
+///     async fn autonomous(&mut self) {
+///         // This task is cancelled once autonomous ends, even though nothing awaits it.
+///         spawn_scoped(async {
+///             loop {
+///                 println!("Still in autonomous!");
+///                 sleep(Duration::from_secs(1)).await;
+///             }
+///         });
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ModeScope {
+    inner: Rc<ScopeInner>,
+}
+
+impl ModeScope {
+    fn new() -> Self {
+        Self {
+            inner: Rc::new(ScopeInner {
+                ended: Cell::new(false),
+                values: RefCell::new(BTreeMap::new()),
+                next_key: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Returns the scope of the competition mode currently being run, if any.
+    ///
+    /// This returns [`None`] when called from outside of a competition mode's task (or from a task
+    /// that was not spawned into a scope).
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT_SCOPE.with(|current| current.borrow().clone())
+    }
+
+    /// Runs a closure with this scope as the [current](ModeScope::current) scope.
+    ///
+    /// The previous scope is restored once the closure returns.
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<ModeScope>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+
+                // The replaced scope is dropped outside of the borrow in case it's the last
+                // reference, since dropping its values could run arbitrary code.
+                let entered = CURRENT_SCOPE.with(|current| current.replace(previous));
+                drop(entered);
+            }
+        }
+
+        let _restore = Restore(CURRENT_SCOPE.with(|current| {
+            current.replace(Some(self.clone()))
+        }));
+        f()
+    }
+
+    /// Keeps `value` alive until this scope ends, at which point it is dropped.
+    ///
+    /// If the scope has already ended, `value` is dropped immediately. The returned key can be
+    /// passed to [`unbind`](Self::unbind) to drop the value early, such as once a task bound to
+    /// the scope has finished.
+    pub fn bind<T: 'static>(&self, value: T) -> BindingKey {
+        let key = BindingKey(self.inner.next_key.get());
+        self.inner.next_key.set(key.0 + 1);
+
+        if self.is_active() {
+            self.inner.values.borrow_mut().insert(key, Box::new(value));
+        }
+
+        key
+    }
+
+    /// Drops a value that was [bound](Self::bind) to this scope before the scope ends.
+    ///
+    /// Does nothing if the value has already been dropped.
+    pub fn unbind(&self, key: BindingKey) {
+        // Values may access the scope while being dropped, so it must not be borrowed here.
+        let value = self.inner.values.borrow_mut().remove(&key);
+        drop(value);
+    }
+
+    /// Returns `true` if the competition runtime is still in the phase that this scope belongs to.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.inner.ended.get()
+    }
+
+    /// Ends the scope, dropping every value bound to it.
+    fn end(&self) {
+        self.inner.ended.set(true);
+
+        // Values may access the scope while being dropped, so it must not be borrowed here.
+        let values = self.inner.values.take();
+        drop(values);
+    }
+}
+
+impl fmt::Debug for ModeScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModeScope")
+            .field("is_active", &self.is_active())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`ModeScope`] owned by a [`CompetitionRuntime`], which is ended when dropped.
+struct OwnedModeScope(ModeScope);
+
+impl Drop for OwnedModeScope {
+    fn drop(&mut self) {
+        self.0.end();
+    }
+}
+
 /// A future which delegates to different futures depending on the current competition mode. i.e., a
 /// tiny async runtime specifically for writing competition programs.
 ///
@@ -345,6 +696,19 @@ pub struct CompetitionRuntime<
     mk_autonomous: MkAutonomous,
     mk_driver: MkDriver,
 
+    // Functions to run when the mode changes.
+    on_exit: Option<ExitHook<Shared>>,
+    on_transition: Option<TransitionHook<Shared>>,
+
+    /// The transitions between modes that have happened so far.
+    history: TransitionHistory,
+
+    /// The mode of the last mode task that was started, if any.
+    last_mode: Option<CompetitionMode>,
+
+    /// A task that runs alongside every phase, or [`None`] if there isn't one or it has finished.
+    always: Option<AlwaysTask<Return>>,
+
     /// A stream of updates to the competition status.
     #[pin]
     updates: CompetitionUpdates,
@@ -355,6 +719,9 @@ pub struct CompetitionRuntime<
     /// The current phase of the competition runtime.
     phase: CompetitionRuntimePhase,
 
+    /// The scope of the current phase, which is ended whenever the phase changes.
+    scope: OwnedModeScope,
+
     /// The task currently running, or [`None`] if no task is running.
     ///
     /// SAFETY:
@@ -440,7 +807,20 @@ where
             _ => {}
         }
 
-        if let Some(Poll::Ready(res)) = this.task.as_mut().map(|task| task.as_mut().poll(cx)) {
+        if let Some(Poll::Ready(res)) = this.always.as_mut().map(|task| task.as_mut().poll(cx)) {
+            if let ControlFlow::Break(val) = res {
+                return Poll::Ready(val);
+            }
+
+            // The task finished without breaking out, so there's nothing more to poll.
+            *this.always = None;
+        }
+
+        if let Some(Poll::Ready(res)) = this
+            .task
+            .as_mut()
+            .map(|task| this.scope.0.enter(|| task.as_mut().poll(cx)))
+        {
             // If a task says to break out of the competition lifecycle, then we pass the return
             // value up.
             if let ControlFlow::Break(val) = res {
@@ -471,8 +851,28 @@ where
             drop(this.task.take());
             let shared = unsafe { &mut *this.shared.get() };
 
+            if let CompetitionRuntimePhase::Mode(mode) = old_phase
+                && let Some(on_exit) = this.on_exit
+            {
+                this.scope.0.enter(|| on_exit(shared, mode));
+            }
+
+            // Stop anything that was tied to the previous phase.
+            *this.scope = OwnedModeScope(ModeScope::new());
+
+            // Note that `last_mode` is updated whenever we enter a mode, even if it didn't change.
+            if let CompetitionRuntimePhase::Mode(to) = *this.phase
+                && let Some(from) = this.last_mode.replace(to)
+                && from != to
+            {
+                this.history.record(from, to);
+                if let Some(on_transition) = this.on_transition {
+                    on_transition(shared, from, to);
+                }
+            }
+
             // Create a new task based on the new competition phase.
-            *this.task = match *this.phase {
+            *this.task = this.scope.0.enter(|| match *this.phase {
                 CompetitionRuntimePhase::Initial => None,
                 CompetitionRuntimePhase::Disconnected => Some((this.mk_disconnected)(shared)),
                 CompetitionRuntimePhase::Connected => Some((this.mk_connected)(shared)),
@@ -485,13 +885,58 @@ where
                 CompetitionRuntimePhase::Mode(CompetitionMode::Driver) => {
                     Some((this.mk_driver)(shared))
                 }
-            };
+            });
+
+            // The new task hasn't been polled yet, so make sure that we're polled again.
+            cx.waker().wake_by_ref();
         }
 
         Poll::Pending
     }
 }
 
+impl<Shared, Return, MkConnected, MkDisconnected, MkDisabled, MkAutonomous, MkDriver>
+    CompetitionRuntime<
+        Shared,
+        Return,
+        MkConnected,
+        MkDisconnected,
+        MkDisabled,
+        MkAutonomous,
+        MkDriver,
+    >
+where
+    MkConnected:
+        for<'t> FnMut(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>,
+    MkDisconnected:
+        for<'t> FnMut(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>,
+    MkDisabled:
+        for<'t> FnMut(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>,
+    MkAutonomous:
+        for<'t> FnMut(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>,
+    MkDriver:
+        for<'t> FnMut(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>,
+{
+    /// Returns the history of mode transitions made by this runtime.
+    ///
+    /// The returned handle refers to the same history that the runtime records into, so it can be
+    /// kept after the runtime is awaited and inspected later on.
+    #[must_use]
+    pub fn history(&self) -> TransitionHistory {
+        self.history.clone()
+    }
+
+    /// Record mode transitions into the given history, rather than a new one.
+    ///
+    /// This is useful for giving the runtime's tasks access to the history. Any transitions that
+    /// were previously recorded by this runtime are not copied over.
+    #[must_use]
+    pub fn with_history(mut self, history: TransitionHistory) -> Self {
+        self.history = history;
+        self
+    }
+}
+
 impl<Shared, Return>
     CompetitionRuntime<
         Shared,
@@ -520,6 +965,10 @@ impl<Shared, Return>
             mk_disabled: default_mk,
             mk_autonomous: default_mk,
             mk_driver: default_mk,
+            on_exit: None,
+            on_transition: None,
+            history: None,
+            always: None,
             _return: PhantomData,
         }
     }
@@ -528,6 +977,10 @@ impl<Shared, Return>
 type DefaultMk<Shared, Return> =
     for<'t> fn(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>;
 
+type ExitHook<Shared> = Box<dyn FnMut(&mut Shared, CompetitionMode)>;
+type TransitionHook<Shared> = Box<dyn FnMut(&mut Shared, CompetitionMode, CompetitionMode)>;
+type AlwaysTask<Return> = Pin<Box<dyn Future<Output = ControlFlow<Return>>>>;
+
 /// A typed builder for [`CompetitionRuntime`] instances.
 pub struct CompetitionBuilder<
     Shared,
@@ -546,6 +999,11 @@ pub struct CompetitionBuilder<
     mk_autonomous: MkAutonomous,
     mk_driver: MkDriver,
 
+    on_exit: Option<ExitHook<Shared>>,
+    on_transition: Option<TransitionHook<Shared>>,
+    history: Option<TransitionHistory>,
+    always: Option<AlwaysTask<Return>>,
+
     // We're invariant in the return type.
     _return: PhantomData<fn(Return) -> Return>,
 }
@@ -572,6 +1030,102 @@ where
     MkDriver:
         for<'t> FnMut(&'t mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 't>>,
 {
+    /// Use the given function to clean up after a competition mode ends.
+    ///
+    /// This runs whenever a disabled, autonomous, or driver control task is stopped, regardless of
+    /// which mode comes next. The mode's task will have already been dropped, so the function is
+    /// free to use the shared data. Since the next task isn't started until this function returns,
+    /// it should finish quickly.
+    #[must_use]
+    pub fn on_exit(mut self, on_exit: impl FnMut(&mut Shared, CompetitionMode) + 'static) -> Self {
+        self.on_exit = Some(Box::new(on_exit));
+        self
+    }
+
+    /// Use the given function to respond to the robot changing from one competition mode to
+    /// another.
+    ///
+    /// This runs before the next mode's task is created, after [`on_exit`](Self::on_exit). If the
+    /// robot is briefly connected or disconnected between the two modes, the transition is still
+    /// reported once the new mode starts. Like `on_exit`, this function should finish quickly.
+    #[must_use]
+    pub fn on_transition(
+        mut self,
+        on_transition: impl FnMut(&mut Shared, CompetitionMode, CompetitionMode) + 'static,
+    ) -> Self {
+        self.on_transition = Some(Box::new(on_transition));
+        self
+    }
+
+    /// Use the given function to create a task that runs for the entire lifetime of the runtime,
+    /// alongside the task for each competition mode.
+    ///
+    /// This is useful for work that shouldn't stop when the mode changes, such as odometry or
+    /// telemetry. The task is polled before the current mode's task every time the runtime is
+    /// polled. If it breaks out of the competition lifecycle, the runtime returns its value.
+    ///
+    /// # Sharing State
+    ///
+    /// Mode tasks have exclusive access to the shared data, so this task can't borrow it. Instead,
+    /// `mk_always` is called once with the shared data so that it can clone handles to any state
+    /// that both tasks need, such as an `Rc<RefCell<T>>`. Avoid holding a [`RefCell`] borrow
+    /// across an `.await`, since the other task will panic if it tries to borrow the value in the
+    /// meantime. State that must stay locked across an `.await` should use an async mutex instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::{cell::Cell, ops::ControlFlow, rc::Rc, time::Duration};
+    ///
+    /// use vexide::{competition::CompetitionRuntime, prelude::*};
+    ///
+    /// struct Robot {
+    ///     distance: Rc<Cell<f64>>,
+    /// }
+    ///
+    /// #[vexide::main]
+    /// async fn main(_peripherals: Peripherals) {
+    ///     let robot = Robot {
+    ///         distance: Rc::new(Cell::new(0.0)),
+    ///     };
+    ///
+    ///     CompetitionRuntime::builder(robot)
+    ///         .always(|robot| {
+    ///             let distance = robot.distance.clone();
+    ///             async move {
+    ///                 loop {
+    ///                     distance.set(distance.get() + 1.0);
+    ///                     sleep(Duration::from_millis(10)).await;
+    ///                 }
+    ///             }
+    ///         })
+    ///         .while_driving(|robot| {
+    ///             Box::pin(async move {
+    ///                 println!("Distance: {}", robot.distance.get());
+    ///                 ControlFlow::<()>::Continue(())
+    ///             })
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    #[must_use]
+    pub fn always<F>(mut self, mk_always: impl FnOnce(&mut Shared) -> F) -> Self
+    where
+        F: Future<Output = ControlFlow<Return>> + 'static,
+    {
+        self.always = Some(Box::pin(mk_always(&mut self.shared)));
+        self
+    }
+
+    /// Record mode transitions into the given history, rather than a new one.
+    ///
+    /// See [`CompetitionRuntime::with_history`] for more information.
+    #[must_use]
+    pub fn with_history(mut self, history: TransitionHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
     /// Finish the builder, returning a [`CompetitionRuntime`] instance.
     pub fn finish(
         self,
@@ -590,9 +1144,15 @@ where
             mk_disabled: self.mk_disabled,
             mk_autonomous: self.mk_autonomous,
             mk_driver: self.mk_driver,
+            on_exit: self.on_exit,
+            on_transition: self.on_transition,
+            history: self.history.unwrap_or_default(),
+            last_mode: None,
+            always: self.always,
             status: status(),
             updates: updates(),
             phase: CompetitionRuntimePhase::Initial,
+            scope: OwnedModeScope(ModeScope::new()),
             task: None,
             shared: UnsafeCell::new(self.shared),
             _pin: PhantomPinned,
@@ -670,6 +1230,10 @@ impl<Shared, Return, MkDisconnected, MkDisabled, MkAutonomous, MkDriver>
             mk_disabled: self.mk_disabled,
             mk_autonomous: self.mk_autonomous,
             mk_driver: self.mk_driver,
+            on_exit: self.on_exit,
+            on_transition: self.on_transition,
+            history: self.history,
+            always: self.always,
             _return: self._return,
         }
     }
@@ -705,6 +1269,10 @@ impl<Shared, Return, MkConnected, MkDisabled, MkAutonomous, MkDriver>
             mk_disabled: self.mk_disabled,
             mk_autonomous: self.mk_autonomous,
             mk_driver: self.mk_driver,
+            on_exit: self.on_exit,
+            on_transition: self.on_transition,
+            history: self.history,
+            always: self.always,
             _return: self._return,
         }
     }
@@ -739,6 +1307,10 @@ impl<Shared, Return, MkConnected, MkDisconnected, MkAutonomous, MkDriver>
             mk_disabled,
             mk_autonomous: self.mk_autonomous,
             mk_driver: self.mk_driver,
+            on_exit: self.on_exit,
+            on_transition: self.on_transition,
+            history: self.history,
+            always: self.always,
             _return: self._return,
         }
     }
@@ -774,6 +1346,10 @@ impl<Shared, Return, MkConnected, MkDisconnected, MkDisabled, MkDriver>
             mk_disabled: self.mk_disabled,
             mk_autonomous,
             mk_driver: self.mk_driver,
+            on_exit: self.on_exit,
+            on_transition: self.on_transition,
+            history: self.history,
+            always: self.always,
             _return: self._return,
         }
     }
@@ -808,11 +1384,77 @@ impl<Shared, Return, MkConnected, MkDisconnected, MkDisabled, MkAutonomous>
             mk_disabled: self.mk_disabled,
             mk_autonomous: self.mk_autonomous,
             mk_driver,
+            on_exit: self.on_exit,
+            on_transition: self.on_transition,
+            history: self.history,
+            always: self.always,
             _return: self._return,
         }
     }
 }
 
+impl<Shared: 'static, Return, MkConnected, MkDisconnected, MkDriver>
+    CompetitionBuilder<
+        Shared,
+        Return,
+        MkConnected,
+        MkDisconnected,
+        DefaultMk<Shared, Return>,
+        DefaultMk<Shared, Return>,
+        MkDriver,
+    >
+{
+    /// Use the given [`AutonSelector`] to choose and run an autonomous routine.
+    ///
+    /// While the robot is disabled, the selector is shown on `ui` so that the driver can choose a
+    /// routine. The UI is checked for input every [`AutonSelector::POLL_INTERVAL`], waiting in
+    /// between using `sleep`, which will usually be `vexide::time::sleep`.
+    ///
+    /// When the robot enters autonomous mode, the chosen routine is run. As with
+    /// [`while_autonomous`](Self::while_autonomous), the routine will NOT be restarted if it
+    /// finishes before the end of the autonomous period.
+    #[allow(clippy::type_complexity)]
+    pub fn auton_selector<F: Future<Output = ()>>(
+        self,
+        selector: AutonSelector<Shared>,
+        ui: impl SelectorUi + 'static,
+        sleep: impl FnMut(Duration) -> F + 'static,
+    ) -> CompetitionBuilder<
+        Shared,
+        Return,
+        MkConnected,
+        MkDisconnected,
+        impl for<'s> FnMut(&'s mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 's>>,
+        impl for<'s> FnMut(&'s mut Shared) -> Pin<Box<dyn Future<Output = ControlFlow<Return>> + 's>>,
+        MkDriver,
+    > {
+        // The UI is reused every time the robot is disabled.
+        let ui = SharedUi(Rc::new(RefCell::new(ui)));
+        let sleep = Rc::new(RefCell::new(sleep));
+        let ui_selector = selector.clone();
+
+        self.while_disabled(move |_| {
+            let selector = ui_selector.clone();
+            let mut ui = ui.clone();
+            let sleep = sleep.clone();
+
+            Box::pin(async move {
+                selector
+                    .show_ui(&mut ui, |duration| (sleep.borrow_mut())(duration))
+                    .await
+            })
+        })
+        .while_autonomous(move |shared| {
+            let selector = selector.clone();
+
+            Box::pin(async move {
+                selector.run_selected(shared).await;
+                ControlFlow::Continue(())
+            })
+        })
+    }
+}
+
 /// A set of functions to run when the competition is in a particular mode.
 ///
 /// This trait allows you to declare different functions on a common robot struct to be executed
@@ -896,6 +1538,25 @@ pub trait Compete: Sized {
     /// Robots may be placed into opcontrol mode at any point in the competition after connecting,
     /// but are typically placed into this mode following the autonomous period.
     async fn driver(&mut self) {}
+
+    /// Runs when the robot leaves a competition mode, regardless of which mode comes next.
+    ///
+    /// This is a good place to put the robot into a safe state, such as by stopping motors. Unlike
+    /// the other functions in this trait, this function is not async and should finish quickly.
+    ///
+    /// See [`CompetitionBuilder::on_exit`] for more information.
+    #[allow(unused_variables)]
+    fn on_exit(&mut self, mode: CompetitionMode) {}
+
+    /// Runs when the robot changes from one competition mode to another, after
+    /// [`on_exit`](Self::on_exit).
+    ///
+    /// Unlike the other functions in this trait, this function is not async and should finish
+    /// quickly.
+    ///
+    /// See [`CompetitionBuilder::on_transition`] for more information.
+    #[allow(unused_variables)]
+    fn on_transition(&mut self, from: CompetitionMode, to: CompetitionMode) {}
 }
 
 /// Extension methods for [`Compete`].
@@ -922,8 +1583,60 @@ pub trait CompeteExt: Compete {
             .while_disabled(|s| Box::pin(async { ControlFlow::Continue(s.disabled().await) }))
             .while_autonomous(|s| Box::pin(async { ControlFlow::Continue(s.autonomous().await) }))
             .while_driving(|s| Box::pin(async { ControlFlow::Continue(s.driver().await) }))
+            .on_exit(Self::on_exit)
+            .on_transition(Self::on_transition)
             .finish()
     }
 }
 
 impl<R: Compete> CompeteExt for R {}
+
+#[cfg(all(test, feature = "sim"))]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+    use crate::time;
+
+    const FIELD_CONTROL: CompetitionStatus =
+        CompetitionStatus::CONNECTED.union(CompetitionStatus::SYSTEM);
+
+    #[test]
+    fn match_clock_follows_paused_clock() {
+        time::pause();
+
+        for format in [MatchFormat::VRC, MatchFormat::VEXU, MatchFormat::SKILLS] {
+            sim::set_status(FIELD_CONTROL.union(CompetitionStatus::DISABLED));
+            let clock = MatchClock::new(format);
+            assert_eq!(clock.remaining(), None);
+
+            sim::set_status(FIELD_CONTROL.union(CompetitionStatus::AUTONOMOUS));
+            assert_eq!(clock.elapsed(), Duration::ZERO);
+            assert_eq!(clock.remaining(), Some(format.autonomous));
+
+            time::advance(Duration::from_secs(5));
+            assert_eq!(clock.elapsed(), Duration::from_secs(5));
+            assert_eq!(
+                clock.remaining(),
+                Some(format.autonomous.saturating_sub(Duration::from_secs(5)))
+            );
+
+            time::advance(format.autonomous);
+            assert_eq!(clock.remaining(), Some(Duration::ZERO));
+
+            sim::set_status(FIELD_CONTROL);
+            assert_eq!(clock.mode(), CompetitionMode::Driver);
+            assert_eq!(clock.remaining(), Some(format.driver));
+
+            time::advance(Duration::from_secs(10));
+            assert_eq!(clock.elapsed(), Duration::from_secs(10));
+            assert_eq!(
+                clock.remaining(),
+                Some(format.driver.saturating_sub(Duration::from_secs(10)))
+            );
+        }
+
+        sim::reset();
+        time::resume();
+    }
+}
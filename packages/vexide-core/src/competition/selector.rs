@@ -0,0 +1,449 @@
+use alloc::{boxed::Box, rc::Rc, string::String, vec::Vec};
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    time::Duration,
+};
+
+/// An autonomous routine registered with an [`AutonSelector`].
+type Routine<Shared> =
+    Box<dyn for<'s> FnMut(&'s mut Shared) -> Pin<Box<dyn Future<Output = ()> + 's>>>;
+
+/// An input from the user of an [`AutonSelector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SelectorInput {
+    /// Select the routine after the currently selected one, wrapping around to the first routine.
+    Next,
+
+    /// Select the routine before the currently selected one, wrapping around to the last routine.
+    Previous,
+
+    /// Select the routine at the given index.
+    Select(usize),
+}
+
+/// A user interface for choosing a routine from an [`AutonSelector`].
+///
+/// This is implemented for the brain's `Display` (using the touchscreen) and for `Controller`
+/// (using the left and right arrow buttons) in `vexide::devices`. A tuple of two interfaces can be
+/// used to accept input from either of them.
+pub trait SelectorUi {
+    /// Shows the names of the available routines, highlighting the one at index `selected`.
+    ///
+    /// This is called whenever the selection changes, as well as once when the interface is first
+    /// shown.
+    fn draw(&mut self, routines: &[&str], selected: usize);
+
+    /// Returns the user's latest input, if there is any.
+    ///
+    /// This is called every [`AutonSelector::POLL_INTERVAL`]. `routine_count` is the number of
+    /// routines that were passed to the last call to [`draw`](Self::draw).
+    fn poll_input(&mut self, routine_count: usize) -> Option<SelectorInput>;
+}
+
+impl<A: SelectorUi, B: SelectorUi> SelectorUi for (A, B) {
+    fn draw(&mut self, routines: &[&str], selected: usize) {
+        self.0.draw(routines, selected);
+        self.1.draw(routines, selected);
+    }
+
+    fn poll_input(&mut self, routine_count: usize) -> Option<SelectorInput> {
+        self.0
+            .poll_input(routine_count)
+            .or_else(|| self.1.poll_input(routine_count))
+    }
+}
+
+impl<T: SelectorUi + ?Sized> SelectorUi for &mut T {
+    fn draw(&mut self, routines: &[&str], selected: usize) {
+        (**self).draw(routines, selected);
+    }
+
+    fn poll_input(&mut self, routine_count: usize) -> Option<SelectorInput> {
+        (**self).poll_input(routine_count)
+    }
+}
+
+/// A [`SelectorUi`] shared between each of the competition runtime's disabled tasks, which is
+/// only borrowed while it's being used.
+pub(super) struct SharedUi<T>(pub Rc<RefCell<T>>);
+
+impl<T> Clone for SharedUi<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: SelectorUi> SelectorUi for SharedUi<T> {
+    fn draw(&mut self, routines: &[&str], selected: usize) {
+        self.0.borrow_mut().draw(routines, selected);
+    }
+
+    fn poll_input(&mut self, routine_count: usize) -> Option<SelectorInput> {
+        self.0.borrow_mut().poll_input(routine_count)
+    }
+}
+
+struct SelectorInner<Shared> {
+    names: Vec<String>,
+    /// Each routine is taken out of its slot while it runs.
+    routines: RefCell<Vec<Option<Routine<Shared>>>>,
+    selected: Cell<usize>,
+}
+
+/// A set of named autonomous routines, one of which can be picked before a match starts.
+///
+/// The driver chooses a routine through a [`SelectorUi`] while the robot is disabled, and the
+/// chosen routine is then run when the robot enters autonomous mode. The easiest way to do this is
+/// with [`CompetitionBuilder::auton_selector`](super::CompetitionBuilder::auton_selector), which
+/// sets up both of these tasks.
+///
+/// Routines are async closures that are given mutable access to the competition runtime's shared
+/// data (usually your robot). `AutonSelector` is a cheaply cloneable handle, so clones of it can
+/// be used to check or change the selection from other tasks.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{ops::ControlFlow, time::Duration};
+///
+/// use vexide::{
+///     competition::{AutonSelector, CompetitionRuntime},
+///     prelude::*,
+/// };
+///
+/// struct Robot {
+///     drive_time: Duration,
+/// }
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let robot = Robot {
+///         drive_time: Duration::from_secs(2),
+///     };
+///
+///     let selector = AutonSelector::new()
+///         .routine("Drive forward", |robot: &mut Robot| {
+///             Box::pin(async move {
+///                 println!("Driving forward...");
+///                 sleep(robot.drive_time).await;
+///             })
+///         })
+///         .routine("Do nothing", |_| Box::pin(async {}));
+///
+///     CompetitionRuntime::builder(robot)
+///         .auton_selector(selector, peripherals.display, sleep)
+///         .while_driving(|_| Box::pin(async { ControlFlow::<()>::Continue(()) }))
+///         .await;
+/// }
+/// ```
+pub struct AutonSelector<Shared> {
+    inner: Rc<SelectorInner<Shared>>,
+}
+
+impl<Shared> AutonSelector<Shared> {
+    /// How often the selector's UI is checked for input, which matches how often the controller
+    /// sends updates to the Brain.
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+    /// Creates a selector with no routines.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(SelectorInner {
+                names: Vec::new(),
+                routines: RefCell::new(Vec::new()),
+                selected: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Adds a routine with the given name.
+    ///
+    /// The first routine added is selected by default.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the selector has been cloned, since routines can't be added to a
+    /// selector that's shared with other handles.
+    #[must_use]
+    pub fn routine(
+        mut self,
+        name: impl Into<String>,
+        routine: impl for<'s> FnMut(&'s mut Shared) -> Pin<Box<dyn Future<Output = ()> + 's>> + 'static,
+    ) -> Self {
+        let inner = Rc::get_mut(&mut self.inner)
+            .expect("routines can't be added to an `AutonSelector` after it has been cloned");
+
+        inner.names.push(name.into());
+        inner.routines.get_mut().push(Some(Box::new(routine)));
+
+        self
+    }
+
+    /// Returns the number of routines in the selector.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.names.len()
+    }
+
+    /// Returns `true` if the selector has no routines.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.names.is_empty()
+    }
+
+    /// Returns the names of every routine, in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.inner.names.iter().map(String::as_str)
+    }
+
+    /// Returns the index of the selected routine, or [`None`] if the selector has no routines.
+    #[must_use]
+    pub fn selected(&self) -> Option<usize> {
+        (!self.is_empty()).then(|| self.inner.selected.get())
+    }
+
+    /// Returns the name of the selected routine, or [`None`] if the selector has no routines.
+    #[must_use]
+    pub fn selected_name(&self) -> Option<&str> {
+        self.selected()
+            .map(|index| self.inner.names[index].as_str())
+    }
+
+    /// Selects the routine at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds.
+    pub fn select(&self, index: usize) {
+        assert!(
+            index < self.len(),
+            "routine index {index} is out of bounds for a selector with {} routines",
+            self.len()
+        );
+        self.inner.selected.set(index);
+    }
+
+    fn apply(&self, input: SelectorInput) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+
+        let selected = self.inner.selected.get();
+        let new = match input {
+            SelectorInput::Next => (selected + 1) % len,
+            SelectorInput::Previous => (selected + len - 1) % len,
+            SelectorInput::Select(index) if index < len => index,
+            SelectorInput::Select(_) => selected,
+        };
+        self.inner.selected.set(new);
+    }
+
+    /// Draws the selector with `ui` if the selection has changed, then applies any new input.
+    fn update_ui(&self, ui: &mut impl SelectorUi, drawn: &mut Option<usize>) {
+        let Some(selected) = self.selected() else {
+            return;
+        };
+
+        if *drawn != Some(selected) {
+            let names = self.names().collect::<Vec<_>>();
+            ui.draw(&names, selected);
+            *drawn = Some(selected);
+        }
+
+        if let Some(input) = ui.poll_input(self.len()) {
+            self.apply(input);
+        }
+    }
+
+    /// Keeps `ui` up to date with the selection, checking it for input every
+    /// [`POLL_INTERVAL`](Self::POLL_INTERVAL).
+    pub(super) async fn show_ui<T, F: Future<Output = ()>>(
+        &self,
+        ui: &mut impl SelectorUi,
+        mut sleep: impl FnMut(Duration) -> F,
+    ) -> T {
+        let mut drawn = None;
+
+        // User interfaces can't notify us of input, so we need to keep checking for it.
+        loop {
+            self.update_ui(ui, &mut drawn);
+            sleep(Self::POLL_INTERVAL).await;
+        }
+    }
+
+    /// Shows the selector on `ui` and lets the user change the selected routine.
+    ///
+    /// The UI is checked for input every [`POLL_INTERVAL`](Self::POLL_INTERVAL), waiting in
+    /// between using `sleep`, which will usually be `vexide::time::sleep`.
+    ///
+    /// The returned future never completes, and is intended to be run while the robot is disabled.
+    pub async fn run_ui<F: Future<Output = ()>>(
+        &self,
+        mut ui: impl SelectorUi,
+        sleep: impl FnMut(Duration) -> F,
+    ) -> ! {
+        self.show_ui(&mut ui, sleep).await
+    }
+
+    /// Runs the selected routine to completion.
+    ///
+    /// If the selector has no routines, this returns immediately.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the selected routine is already running.
+    pub async fn run_selected(&self, shared: &mut Shared) {
+        let Some(index) = self.selected() else {
+            return;
+        };
+
+        // Don't hold onto the borrow while the routine runs, so that other routines can still be
+        // run in the meantime.
+        let mut routine = self.inner.routines.borrow_mut()[index]
+            .take()
+            .expect("the selected autonomous routine is already running");
+
+        let future = routine(shared);
+
+        // Put the routine back once it's done, even if it's cancelled partway through.
+        let _restore = Restore {
+            routines: &self.inner.routines,
+            index,
+            routine: Some(routine),
+        };
+
+        future.await;
+    }
+}
+
+/// Returns a routine to its slot in an [`AutonSelector`] when dropped.
+struct Restore<'a, Shared> {
+    routines: &'a RefCell<Vec<Option<Routine<Shared>>>>,
+    index: usize,
+    routine: Option<Routine<Shared>>,
+}
+
+impl<Shared> Drop for Restore<'_, Shared> {
+    fn drop(&mut self) {
+        self.routines.borrow_mut()[self.index] = self.routine.take();
+    }
+}
+
+impl<Shared> Clone for AutonSelector<Shared> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Shared> Default for AutonSelector<Shared> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Shared> fmt::Debug for AutonSelector<Shared> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutonSelector")
+            .field("routines", &self.inner.names)
+            .field("selected", &self.selected())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+    use core::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    fn selector() -> AutonSelector<Vec<&'static str>> {
+        AutonSelector::new()
+            .routine("left", |log: &mut Vec<_>| {
+                Box::pin(async move { log.push("left") })
+            })
+            .routine("middle", |log: &mut Vec<_>| {
+                Box::pin(async move { log.push("middle") })
+            })
+            .routine("right", |log: &mut Vec<_>| {
+                Box::pin(async move { log.push("right") })
+            })
+    }
+
+    #[test]
+    fn inputs_wrap_around() {
+        let selector = selector();
+        assert_eq!(selector.selected_name(), Some("left"));
+
+        selector.apply(SelectorInput::Previous);
+        assert_eq!(selector.selected_name(), Some("right"));
+
+        selector.apply(SelectorInput::Next);
+        assert_eq!(selector.selected_name(), Some("left"));
+
+        selector.apply(SelectorInput::Next);
+        selector.apply(SelectorInput::Next);
+        assert_eq!(selector.selected(), Some(2));
+    }
+
+    #[test]
+    fn selects_by_index() {
+        let selector = selector();
+
+        selector.apply(SelectorInput::Select(1));
+        assert_eq!(selector.selected_name(), Some("middle"));
+
+        // Out of range indices are ignored.
+        selector.apply(SelectorInput::Select(3));
+        assert_eq!(selector.selected_name(), Some("middle"));
+
+        selector.select(2);
+        assert_eq!(selector.selected_name(), Some("right"));
+    }
+
+    #[test]
+    #[should_panic = "out of bounds"]
+    fn select_out_of_bounds_panics() {
+        selector().select(3);
+    }
+
+    #[test]
+    fn empty_selector_has_no_selection() {
+        let selector = AutonSelector::<()>::new();
+
+        selector.apply(SelectorInput::Next);
+        assert!(selector.is_empty());
+        assert_eq!(selector.selected(), None);
+        assert_eq!(selector.selected_name(), None);
+    }
+
+    #[test]
+    fn runs_selected_routine() {
+        let selector = selector();
+        selector.select(1);
+
+        let mut log = Vec::new();
+        for _ in 0..2 {
+            let run = pin!(selector.run_selected(&mut log));
+            assert_eq!(
+                run.poll(&mut Context::from_waker(Waker::noop())),
+                Poll::Ready(())
+            );
+        }
+
+        assert_eq!(log, vec!["middle", "middle"]);
+    }
+}
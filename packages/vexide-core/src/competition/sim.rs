@@ -0,0 +1,292 @@
+//! Scripted competition control for testing.
+//!
+//! Testing a [`Compete`](super::Compete) implementation normally requires a field controller or
+//! competition switch. This module allows tests running on a host computer to simulate one instead:
+//! while a simulation is active, [`status`](super::status) and [`updates`](super::updates) report
+//! the simulated competition status rather than the one from VEXos.
+//!
+//! The status can be set directly with [`set_status`], or changed over time by playing back a
+//! [`Timeline`]. Simulations are local to the current thread, so tests running in parallel don't
+//! interfere with each other.
+//!
+//! This module is only available with the `test-util` feature.
+//!
+//! # Examples
+//!
+//! Combined with the runtime's paused clock, a full match can be simulated instantly:
+//!
+//! ```
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! use vexide::{competition::sim::Timeline, prelude::*, time};
+//!
+//! struct Robot {
+//!     log: Rc<RefCell<Vec<&'static str>>>,
+//! }
+//!
+//! impl Compete for Robot {
+//!     async fn autonomous(&mut self) {
+//!         self.log.borrow_mut().push("autonomous");
+//!     }
+//!
+//!     async fn driver(&mut self) {
+//!         self.log.borrow_mut().push("driver");
+//!     }
+//! }
+//!
+//! #[vexide::test]
+//! async fn runs_each_mode(_peripherals: Peripherals) {
+//!     time::pause();
+//!
+//!     let log = Rc::new(RefCell::new(Vec::new()));
+//!     let _robot = spawn(Robot { log: log.clone() }.compete());
+//!
+//!     Timeline::vrc_match().play(time::sleep).await;
+//!     assert_eq!(*log.borrow(), ["autonomous", "driver"]);
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::{
+    cell::{Cell, RefCell},
+    future::Future,
+    task::Waker,
+    time::Duration,
+};
+use std::thread_local;
+
+use super::CompetitionStatus;
+
+thread_local! {
+    /// The simulated competition status, or [`None`] if no simulation is active.
+    static STATUS: Cell<Option<CompetitionStatus>> = const { Cell::new(None) };
+
+    /// Tasks waiting for the simulated status to change.
+    static WAKERS: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starts simulating the given competition status, or changes the status of the active simulation.
+pub fn set_status(status: CompetitionStatus) {
+    STATUS.set(Some(status));
+    wake_all();
+}
+
+/// Stops the active simulation, if any, so that the competition status reported by VEXos is used
+/// again.
+pub fn reset() {
+    STATUS.set(None);
+    wake_all();
+}
+
+/// Returns `true` if a simulation is active on the current thread.
+#[must_use]
+pub fn is_active() -> bool {
+    STATUS.get().is_some()
+}
+
+/// Returns the simulated competition status, or [`None`] if no simulation is active.
+pub(crate) fn status() -> Option<CompetitionStatus> {
+    STATUS.get()
+}
+
+/// Registers a waker to be woken the next time the simulated status changes.
+///
+/// Returns `false` if no simulation is active, in which case the waker isn't registered.
+pub(crate) fn register_waker(waker: &Waker) -> bool {
+    if !is_active() {
+        return false;
+    }
+
+    WAKERS.with_borrow_mut(|wakers| {
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    });
+
+    true
+}
+
+fn wake_all() {
+    for waker in WAKERS.take() {
+        waker.wake();
+    }
+}
+
+/// A script of competition statuses to simulate, each lasting for some amount of time.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use vexide::competition::sim::Timeline;
+///
+/// // An autonomous period that is interrupted by the field control cable being unplugged.
+/// let timeline = Timeline::new()
+///     .disabled(Duration::from_secs(1))
+///     .autonomous(Duration::from_secs(5))
+///     .disconnected(Duration::from_secs(1));
+///
+/// assert_eq!(timeline.duration(), Duration::from_secs(7));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[must_use = "a timeline does nothing unless you call `play` on it"]
+pub struct Timeline {
+    steps: Vec<(CompetitionStatus, Duration)>,
+}
+
+impl Timeline {
+    /// The status reported while connected to field control.
+    const FIELD_CONTROL: CompetitionStatus =
+        CompetitionStatus::CONNECTED.union(CompetitionStatus::SYSTEM);
+
+    /// Creates an empty timeline.
+    pub const fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Creates a timeline for a standard VRC match.
+    ///
+    /// The robot is connected to field control and disabled for a second, then runs a 15 second
+    /// autonomous period and a 1 minute 45 second driver control period with a second of being
+    /// disabled in between. Finally, the robot is disabled again for a second.
+    pub fn vrc_match() -> Self {
+        Self::new()
+            .disabled(Duration::from_secs(1))
+            .autonomous(Duration::from_secs(15))
+            .disabled(Duration::from_secs(1))
+            .driver(Duration::from_secs(105))
+            .disabled(Duration::from_secs(1))
+    }
+
+    /// Adds a step that reports `status` for `duration`.
+    pub fn then(mut self, status: CompetitionStatus, duration: Duration) -> Self {
+        self.steps.push((status, duration));
+        self
+    }
+
+    /// Adds a step where the robot is connected to field control and disabled.
+    pub fn disabled(self, duration: Duration) -> Self {
+        self.then(
+            Self::FIELD_CONTROL.union(CompetitionStatus::DISABLED),
+            duration,
+        )
+    }
+
+    /// Adds a step where the robot is connected to field control and in autonomous mode.
+    pub fn autonomous(self, duration: Duration) -> Self {
+        self.then(
+            Self::FIELD_CONTROL.union(CompetitionStatus::AUTONOMOUS),
+            duration,
+        )
+    }
+
+    /// Adds a step where the robot is connected to field control and in driver control mode.
+    pub fn driver(self, duration: Duration) -> Self {
+        self.then(Self::FIELD_CONTROL, duration)
+    }
+
+    /// Adds a step where the robot is not connected to a competition controller.
+    pub fn disconnected(self, duration: Duration) -> Self {
+        self.then(CompetitionStatus::empty(), duration)
+    }
+
+    /// Returns the total duration of every step in the timeline.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.steps.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Plays back the timeline, setting the simulated status at the start of each step and then
+    /// waiting for the step's duration using `sleep`.
+    ///
+    /// `sleep` will usually be `vexide::time::sleep`. The status of the last step remains active
+    /// after the timeline finishes, until it is changed or the simulation is [`reset`].
+    pub async fn play<F: Future<Output = ()>>(self, mut sleep: impl FnMut(Duration) -> F) {
+        for (status, duration) in self.steps {
+            set_status(status);
+            sleep(duration).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{sync::Arc, task::Wake};
+    use core::{
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll},
+    };
+
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn status_changes_wake_registered_tasks() {
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+
+        // Nothing is registered while no simulation is active.
+        assert!(!register_waker(&waker));
+
+        set_status(CompetitionStatus::DISABLED);
+        assert!(register_waker(&waker));
+        assert!(register_waker(&waker));
+        set_status(CompetitionStatus::AUTONOMOUS);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert_eq!(status(), Some(CompetitionStatus::AUTONOMOUS));
+
+        assert!(register_waker(&waker));
+        reset();
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+        assert_eq!(status(), None);
+        assert!(!is_active());
+    }
+
+    #[test]
+    fn timeline_duration_sums_steps() {
+        assert_eq!(Timeline::new().duration(), Duration::ZERO);
+        assert_eq!(Timeline::vrc_match().duration(), Duration::from_secs(123));
+        assert_eq!(
+            Timeline::new()
+                .autonomous(Duration::from_secs(1))
+                .disconnected(Duration::from_millis(500))
+                .duration(),
+            Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn play_sets_each_status_before_sleeping() {
+        let steps = RefCell::new(Vec::new());
+        let timeline = Timeline::new()
+            .disabled(Duration::from_secs(1))
+            .autonomous(Duration::from_secs(2))
+            .driver(Duration::from_secs(3));
+
+        let play = pin!(timeline.clone().play(|duration| {
+            steps.borrow_mut().push((status().unwrap(), duration));
+            async {}
+        }));
+        assert_eq!(
+            play.poll(&mut Context::from_waker(Waker::noop())),
+            Poll::Ready(())
+        );
+
+        assert_eq!(*steps.borrow(), timeline.steps);
+
+        // The last status is still simulated after the timeline finishes.
+        assert_eq!(status(), Some(Timeline::FIELD_CONTROL));
+        reset();
+    }
+}
@@ -18,6 +18,8 @@
 #![feature(never_type)]
 
 extern crate alloc;
+// Every target that vexide supports has `std`, which provides thread-locals.
+extern crate std;
 
 pub mod backtrace;
 pub mod competition;
@@ -108,6 +108,55 @@ impl CodeSignature {
     pub const fn options(&self) -> ProgramOptions {
         ProgramOptions::from_bits_retain(self.0.options)
     }
+
+    /// Returns the 16-byte header of this signature as it appears at the start of a program.
+    #[must_use]
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        let fields = [self.0.magic, self.0.r#type, self.0.owner, self.0.options];
+
+        let mut bytes = [0; 16];
+        let mut i = 0;
+        while i < fields.len() {
+            let field = fields[i].to_le_bytes();
+            bytes[i * 4] = field[0];
+            bytes[i * 4 + 1] = field[1];
+            bytes[i * 4 + 2] = field[2];
+            bytes[i * 4 + 3] = field[3];
+            i += 1;
+        }
+
+        bytes
+    }
+
+    /// Reads a signature from the 16-byte header at the start of a program.
+    ///
+    /// Returns [`None`] if the header isn't a valid code signature.
+    #[must_use]
+    pub const fn from_bytes(bytes: [u8; 16]) -> Option<Self> {
+        const fn field(bytes: &[u8; 16], i: usize) -> u32 {
+            u32::from_le_bytes([
+                bytes[i * 4],
+                bytes[i * 4 + 1],
+                bytes[i * 4 + 2],
+                bytes[i * 4 + 3],
+            ])
+        }
+
+        let signature = vex_sdk::vcodesig {
+            magic: field(&bytes, 0),
+            r#type: field(&bytes, 1),
+            owner: field(&bytes, 2),
+            options: field(&bytes, 3),
+        };
+
+        // Make sure that the type and owner can be read back without panicking.
+        if signature.magic != vex_sdk::V5_SIG_MAGIC || signature.r#type != 0 || signature.owner > 2
+        {
+            return None;
+        }
+
+        Some(Self(signature, [0; 4]))
+    }
 }
 
 /// Returns the code signature of the currently running program.
@@ -1,9 +1,18 @@
 //! Extended VEXos system time APIs.
+//!
+//! With the `sim` feature enabled, this module also provides the clock used by vexide's async
+//! runtime, which can be [paused](pause) so that tests of time-dependent code run instantly and
+//! deterministically. The clock is local to the current thread, so tests running in parallel do not
+//! affect each other. Most users will want to use these functions through `vexide::time`.
 
+#[cfg(feature = "sim")]
+use core::cell::Cell;
 use core::{
     ops::{Add, AddAssign, Sub, SubAssign},
     time::Duration,
 };
+#[cfg(feature = "sim")]
+use std::{thread_local, time::Instant};
 
 use vex_sdk::{vexSystemHighResTimeGet, vexSystemPowerupTimeGet, vexSystemTimeGet};
 
@@ -29,6 +38,118 @@ pub fn user_uptime() -> Duration {
     Duration::from_micros(unsafe { vexSystemHighResTimeGet() })
 }
 
+/// Returns how long the program has been running according to the runtime's clock.
+///
+/// This is the same as [`user_uptime`], except in simulations, where it follows the [pausable
+/// clock](now) and counts from the first time that the clock was used on the current thread.
+pub(crate) fn clock_uptime() -> Duration {
+    #[cfg(feature = "sim")]
+    return CLOCK.with(|clock| clock.now() - clock.epoch);
+
+    #[cfg(not(feature = "sim"))]
+    return user_uptime();
+}
+
+/// The runtime's clock in simulations, which may be paused.
+#[cfg(feature = "sim")]
+struct Clock {
+    /// The clock's reading when it was first used on this thread.
+    epoch: Instant,
+    /// The clock's reading while it is paused.
+    paused_at: Cell<Option<Instant>>,
+    /// The real time and the clock's reading when it was last resumed. While running, the clock
+    /// moves forward from that reading at the same rate as real time.
+    resumed_at: Cell<Option<(Instant, Instant)>>,
+}
+
+#[cfg(feature = "sim")]
+thread_local! {
+    static CLOCK: Clock = Clock {
+        epoch: Instant::now(),
+        paused_at: Cell::new(None),
+        resumed_at: Cell::new(None),
+    };
+}
+
+#[cfg(feature = "sim")]
+impl Clock {
+    fn now(&self) -> Instant {
+        if let Some(paused_at) = self.paused_at.get() {
+            return paused_at;
+        }
+
+        match self.resumed_at.get() {
+            Some((real, reading)) => reading + real.elapsed(),
+            None => Instant::now(),
+        }
+    }
+}
+
+/// Returns the current time according to the runtime's clock.
+///
+/// This is the same as [`Instant::now`], unless the clock has been [paused](pause) or
+/// [advanced](advance).
+#[cfg(feature = "sim")]
+#[must_use]
+pub fn now() -> Instant {
+    CLOCK.with(Clock::now)
+}
+
+/// Pauses the runtime's clock on the current thread.
+///
+/// While the clock is paused, time only moves forward when [`advance`] is called, or when every
+/// task is waiting on a timer, in which case vexide's async runtime immediately skips ahead to the
+/// next timer's deadline. Calling this while the clock is already paused does nothing.
+#[cfg(feature = "sim")]
+pub fn pause() {
+    CLOCK.with(|clock| {
+        if clock.paused_at.get().is_none() {
+            clock.paused_at.set(Some(clock.now()));
+        }
+    });
+}
+
+/// Resumes the runtime's clock on the current thread after it was [paused](pause).
+///
+/// The clock continues from the time that it was paused at, plus any time it was
+/// [advanced](advance) by. Calling this while the clock isn't paused does nothing.
+#[cfg(feature = "sim")]
+pub fn resume() {
+    CLOCK.with(|clock| {
+        // Continue from the paused reading rather than jumping forward by however long the clock
+        // was paused for.
+        if let Some(paused_at) = clock.paused_at.take() {
+            clock.resumed_at.set(Some((Instant::now(), paused_at)));
+        }
+    });
+}
+
+/// Returns `true` if the runtime's clock is [paused](pause) on the current thread.
+#[cfg(feature = "sim")]
+#[must_use]
+pub fn is_paused() -> bool {
+    CLOCK.with(|clock| clock.paused_at.get().is_some())
+}
+
+/// Moves the paused clock forward by `duration`.
+///
+/// Any timers with a deadline that has now passed will complete the next time the runtime checks
+/// on them.
+///
+/// # Panics
+///
+/// Panics if the clock is not [paused](pause).
+#[cfg(feature = "sim")]
+pub fn advance(duration: Duration) {
+    CLOCK.with(|clock| {
+        let paused_at = clock
+            .paused_at
+            .get()
+            .expect("the clock must be paused to advance it");
+        clock.paused_at.set(Some(paused_at + duration));
+    });
+}
+
 /// A timestamp recorded by the Brain's low-resolution private timer.
 ///
 /// This type is not in sync with [`Instant`], which instead uses the brain's global high-resolution
@@ -258,3 +379,53 @@ impl Sub<LowResolutionTime> for LowResolutionTime {
         self.duration_since(other)
     }
 }
+
+#[cfg(all(test, feature = "sim"))]
+mod test {
+    use vex_sdk_mock as _;
+
+    use super::*;
+
+    #[test]
+    fn resumed_clock_continues_from_pause() {
+        pause();
+        let paused_at = now();
+        advance(Duration::from_secs(5));
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(now() - paused_at, Duration::from_secs(5));
+
+        resume();
+        let resumed_at = now();
+        assert!(resumed_at - paused_at >= Duration::from_secs(5));
+        assert!(resumed_at - paused_at < Duration::from_secs(5) + Duration::from_millis(20));
+
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(now() - resumed_at >= Duration::from_millis(10));
+
+        // Pausing again picks up where the resumed clock left off.
+        pause();
+        assert!(now() >= resumed_at + Duration::from_millis(10));
+        resume();
+    }
+
+    #[test]
+    fn clock_uptime_follows_paused_clock() {
+        pause();
+        let start = clock_uptime();
+
+        advance(Duration::from_secs(15));
+        assert_eq!(
+            clock_uptime().saturating_sub(start),
+            Duration::from_secs(15)
+        );
+
+        resume();
+    }
+
+    #[test]
+    #[should_panic = "the clock must be paused to advance it"]
+    fn advancing_running_clock_panics() {
+        advance(Duration::from_secs(1));
+    }
+}
@@ -18,6 +18,7 @@
 use vex_sdk::{vexDeviceAdiValueGet, vexDeviceAdiValueSet};
 
 use super::{AdiDevice, AdiDeviceType, AdiPort, PortError};
+use crate::safe_state;
 
 /// Logic level of a digital pin.
 ///
@@ -439,6 +440,41 @@ impl AdiDigitalOut {
     pub fn toggle(&mut self) -> Result<(), PortError> {
         self.set_level(!self.level()?)
     }
+
+    /// Registers the logic level that the output should be set to if the program panics or
+    /// crashes.
+    ///
+    /// This is useful for putting pneumatics into a safe position. The output is set to this level
+    /// by [`safe_state::apply`], which vexide's panic hook and abort handler call before reporting
+    /// an error. This replaces any safe state previously registered on the output's port, and
+    /// stays registered until it is cleared with [`AdiDigitalOut::clear_safe_state`] or the output
+    /// is converted back into an [`AdiPort`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use vexide::{adi::digital::LogicLevel, prelude::*};
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let clamp = AdiDigitalOut::new(peripherals.adi_a);
+    ///
+    ///     // Release the clamp if the program crashes.
+    ///     clamp.set_safe_state(LogicLevel::Low);
+    /// }
+    /// ```
+    ///
+    /// [`safe_state::apply`]: crate::safe_state::apply
+    pub fn set_safe_state(&self, level: LogicLevel) {
+        safe_state::set_digital_out(&self.port, Some(level));
+    }
+
+    /// Removes the output's safe state, so that it isn't changed if the program panics or crashes.
+    ///
+    /// See [`AdiDigitalOut::set_safe_state`] for more information.
+    pub fn clear_safe_state(&self) {
+        safe_state::set_digital_out(&self.port, None);
+    }
 }
 
 impl AdiDevice<1> for AdiDigitalOut {
@@ -85,6 +85,7 @@ use core::time::Duration;
 
 use crate::{
     adi::{encoder::AdiEncoder, range_finder::AdiRangeFinder},
+    safe_state,
     smart::PortError,
 };
 
@@ -219,7 +220,15 @@ impl<T: AdiDevice<1>> From<T> for AdiPort {
     fn from(device: T) -> Self {
         // SAFETY: We can do this, since we ensure that the old Smart Port was disposed of.
         // This can effectively be thought as a move out of the device's private `port` field.
-        unsafe { Self::new(device.port_numbers()[0], device.expander_port_number()) }
+        let port = unsafe { Self::new(device.port_numbers()[0], device.expander_port_number()) };
+
+        // A digital output's safe state shouldn't outlive it, since the port may be used for a
+        // different device afterwards.
+        if device.device_type() == AdiDeviceType::DigitalOut {
+            safe_state::set_digital_out(&port, None);
+        }
+
+        port
     }
 }
 
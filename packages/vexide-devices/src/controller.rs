@@ -5,16 +5,22 @@
 
 use alloc::{
     ffi::{CString, NulError},
+    format,
     string::{String, ToString},
 };
-use core::{cell::RefCell, future::Future, task::Poll, time::Duration};
+use core::{
+    cell::{Cell, RefCell},
+    future::Future,
+    task::Poll,
+    time::Duration,
+};
 
 use snafu::{Snafu, ensure};
 use vex_sdk::{
     V5_ControllerId, V5_ControllerIndex, V5_ControllerStatus, vexControllerConnectionStatusGet,
     vexControllerGet, vexControllerTextSet,
 };
-use vexide_core::competition::{self, CompetitionMode};
+use vexide_core::competition::{self, CompetitionMode, SelectorInput, SelectorUi};
 
 /// Represents the state of a button on the controller.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -351,6 +357,10 @@ impl From<ControllerConnection> for V5_ControllerStatus {
 pub struct Controller {
     id: ControllerId,
     prev_button_states: RefCell<ButtonStates>,
+    /// Whether the left and right arrow buttons were pressed the last time they were read by the
+    /// [`SelectorUi`] implementation. This is tracked separately from `prev_button_states` so that
+    /// the selector doesn't consume presses seen by [`Controller::state`].
+    prev_selector_arrows: Cell<(bool, bool)>,
 }
 
 impl Controller {
@@ -390,6 +400,7 @@ impl Controller {
                 r2: false,
                 power: false,
             }),
+            prev_selector_arrows: Cell::new((false, false)),
         }
     }
 
@@ -1006,6 +1017,43 @@ impl Controller {
     }
 }
 
+/// Shows the selected routine on the controller's screen. The left and right arrow buttons cycle
+/// through the routines.
+///
+/// Unlike [`Controller::state`], the arrow buttons are read even when the robot isn't in driver
+/// control, since routines are usually chosen while the robot is disabled.
+impl SelectorUi for Controller {
+    fn draw(&mut self, routines: &[&str], selected: usize) {
+        let text = format!("{}/{} {}", selected + 1, routines.len(), routines[selected]);
+        let text = format!(
+            "{:<width$.width$}",
+            text.replace('\0', ""),
+            width = Self::MAX_COLUMNS
+        );
+
+        // This may fail if the screen was written to recently, but there's no good way to retry.
+        _ = self.try_set_text(text, 1, 1);
+    }
+
+    fn poll_input(&mut self, _routine_count: usize) -> Option<SelectorInput> {
+        let raw_id = self.id.into();
+        let left = unsafe { vexControllerGet(raw_id, V5_ControllerIndex::ButtonLeft) } != 0;
+        let right = unsafe { vexControllerGet(raw_id, V5_ControllerIndex::ButtonRight) } != 0;
+
+        let (prev_left, prev_right) = self.prev_selector_arrows.replace((left, right));
+        let left_pressed = left && !prev_left;
+        let right_pressed = right && !prev_right;
+
+        if right_pressed {
+            Some(SelectorInput::Next)
+        } else if left_pressed {
+            Some(SelectorInput::Previous)
+        } else {
+            None
+        }
+    }
+}
+
 /// Errors that can occur when interacting with the controller.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Snafu)]
 pub enum ControllerError {
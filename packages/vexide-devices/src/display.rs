@@ -18,6 +18,7 @@ use vex_sdk::{
     vexDisplayRectFill, vexDisplayScroll, vexDisplayScrollRect, vexDisplayString,
     vexDisplayStringHeightGet, vexDisplayStringWidthGet, vexDisplayTextSize, vexTouchDataGet,
 };
+use vexide_core::competition::{SelectorInput, SelectorUi};
 
 use crate::{color::Color, math::Point2};
 
@@ -951,6 +952,73 @@ impl Display {
     }
 }
 
+/// The number of routines shown in each column of an autonomous selector.
+const SELECTOR_ROWS: usize = 6;
+
+impl Display {
+    /// Returns the rectangle that an autonomous selector draws the routine at `index` in.
+    fn selector_cell(index: usize, routine_count: usize) -> Rect {
+        let columns = routine_count.div_ceil(SELECTOR_ROWS).max(1);
+        let width = Self::HORIZONTAL_RESOLUTION / columns as i16;
+        let height = Self::VERTICAL_RESOLUTION / SELECTOR_ROWS as i16;
+
+        let x = (index / SELECTOR_ROWS) as i16 * width;
+        let y = (index % SELECTOR_ROWS) as i16 * height;
+
+        Rect::new([x, y], [x + width, y + height])
+    }
+}
+
+/// Shows routines in a grid on the display. Tapping a routine selects it.
+impl SelectorUi for Display {
+    fn draw(&mut self, routines: &[&str], selected: usize) {
+        self.erase(Color::BLACK);
+
+        for (index, name) in routines.iter().enumerate() {
+            let cell = Self::selector_cell(index, routines.len());
+            let (background, foreground) = if index == selected {
+                (Color::WHITE, Color::BLACK)
+            } else {
+                (Color::BLACK, Color::WHITE)
+            };
+
+            self.fill(&cell, background);
+            self.stroke(&cell, Color::GRAY);
+            self.draw_text(
+                &Text::from_string_aligned(
+                    name.replace('\0', ""),
+                    Font::new(FontSize::SMALL, FontFamily::Proportional),
+                    [
+                        (cell.top_left.x + cell.bottom_right.x) / 2,
+                        (cell.top_left.y + cell.bottom_right.y) / 2,
+                    ],
+                    Alignment::Center,
+                    Alignment::Center,
+                ),
+                foreground,
+                None,
+            );
+        }
+
+        self.render();
+    }
+
+    fn poll_input(&mut self, routine_count: usize) -> Option<SelectorInput> {
+        let touch = self.touch_status();
+        if touch.state != TouchState::Pressed {
+            return None;
+        }
+
+        (0..routine_count)
+            .find(|&index| {
+                let cell = Self::selector_cell(index, routine_count);
+                (cell.top_left.x..cell.bottom_right.x).contains(&touch.point.x)
+                    && (cell.top_left.y..cell.bottom_right.y).contains(&touch.point.y)
+            })
+            .map(SelectorInput::Select)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::display::Rect;
@@ -48,4 +48,5 @@ pub mod controller;
 pub mod display;
 pub mod math;
 pub mod peripherals;
+pub mod safe_state;
 pub mod smart;
@@ -0,0 +1,173 @@
+//! Safe states for actuators after a fatal error.
+//!
+//! When a program panics or hits a CPU fault, the devices it was controlling keep their last
+//! command until VEXos notices that the program has stopped. For a heavy lift or a pneumatic
+//! mechanism, that can be dangerous. This module keeps a registry of motors and digital outputs
+//! (such as pneumatic solenoids) along with the state each one should be put into when that
+//! happens.
+//!
+//! Devices are registered with [`Motor::set_safe_state`] and [`AdiDigitalOut::set_safe_state`].
+//! vexide's panic hook and abort handler call [`apply`] before reporting an error, which drives
+//! every registered device to its safe state.
+//!
+//! The registry is stored in fixed-size atomic tables, so it can be used from an exception
+//! handler without locking or allocating.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vexide::{adi::digital::LogicLevel, prelude::*, smart::motor::BrakeMode};
+//!
+//! #[vexide::main]
+//! async fn main(peripherals: Peripherals) {
+//!     let lift = Motor::new(peripherals.port_1, Gearset::Red, Direction::Forward);
+//!     let clamp = AdiDigitalOut::new(peripherals.adi_a);
+//!
+//!     // Hold the lift in place and release the clamp if the program crashes.
+//!     lift.set_safe_state(BrakeMode::Hold);
+//!     clamp.set_safe_state(LogicLevel::Low);
+//! }
+//! ```
+//!
+//! [`Motor::set_safe_state`]: crate::smart::motor::Motor::set_safe_state
+//! [`AdiDigitalOut::set_safe_state`]: crate::adi::digital::AdiDigitalOut::set_safe_state
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use vex_sdk::{
+    V5_AdiPortConfiguration, vexDeviceAdiPortConfigGet, vexDeviceAdiValueSet, vexDeviceGetByIndex,
+    vexDeviceMotorBrakeModeSet, vexDeviceMotorVelocitySet, vexDeviceMotorVoltageSet,
+};
+
+use crate::{
+    adi::{AdiPort, digital::LogicLevel},
+    smart::{SmartDeviceType, motor::BrakeMode, validate_port},
+};
+
+/// The number of Smart Ports on the brain.
+const SMART_PORTS: usize = 21;
+
+/// The number of ADI ports on the brain or on an ADI expander.
+const ADI_PORTS: usize = 8;
+
+/// An entry that has no safe state registered.
+const UNREGISTERED: u8 = 0;
+
+/// The safe state of the motor on each Smart Port, indexed by port number minus one.
+static MOTORS: [AtomicU8; SMART_PORTS] = [const { AtomicU8::new(UNREGISTERED) }; SMART_PORTS];
+
+/// The safe state of the digital output on each ADI port.
+///
+/// Ports on the brain are stored after those on each of the 21 possible ADI expanders.
+static DIGITAL_OUTS: [AtomicU8; (SMART_PORTS + 1) * ADI_PORTS] =
+    [const { AtomicU8::new(UNREGISTERED) }; (SMART_PORTS + 1) * ADI_PORTS];
+
+const fn encode_brake_mode(mode: BrakeMode) -> u8 {
+    match mode {
+        BrakeMode::Coast => 1,
+        BrakeMode::Brake => 2,
+        BrakeMode::Hold => 3,
+    }
+}
+
+const fn decode_brake_mode(state: u8) -> Option<BrakeMode> {
+    match state {
+        1 => Some(BrakeMode::Coast),
+        2 => Some(BrakeMode::Brake),
+        3 => Some(BrakeMode::Hold),
+        _ => None,
+    }
+}
+
+const fn encode_level(level: LogicLevel) -> u8 {
+    match level {
+        LogicLevel::Low => 1,
+        LogicLevel::High => 2,
+    }
+}
+
+/// Returns the index of an ADI port in [`DIGITAL_OUTS`].
+fn digital_out_index(port: &AdiPort) -> usize {
+    port.expander_index() as usize * ADI_PORTS + port.index() as usize
+}
+
+/// Sets the safe state of the motor on the given Smart Port.
+pub(crate) fn set_motor(port_number: u8, mode: Option<BrakeMode>) {
+    MOTORS[usize::from(port_number - 1)].store(
+        mode.map_or(UNREGISTERED, encode_brake_mode),
+        Ordering::Relaxed,
+    );
+}
+
+/// Sets the safe state of the digital output on the given ADI port.
+pub(crate) fn set_digital_out(port: &AdiPort, level: Option<LogicLevel>) {
+    DIGITAL_OUTS[digital_out_index(port)]
+        .store(level.map_or(UNREGISTERED, encode_level), Ordering::Relaxed);
+}
+
+/// Drives every registered device to its safe state.
+///
+/// Motors are braked with their registered [`BrakeMode`], and digital outputs are set to their
+/// registered [`LogicLevel`]. Registered devices that are no longer connected, or whose port has
+/// since been used for a different kind of device, are skipped.
+///
+/// This is called automatically by vexide's panic hook and abort handler, but may also be called
+/// directly, such as from a custom panic handler or an emergency stop button.
+pub fn apply() {
+    for (index, state) in MOTORS.iter().enumerate() {
+        let Some(mode) = decode_brake_mode(state.load(Ordering::Relaxed)) else {
+            continue;
+        };
+
+        if validate_port(index as u8 + 1, SmartDeviceType::Motor).is_err() {
+            continue;
+        }
+
+        // This matches `Motor::brake`, which brakes by running at 0rpm in the given brake mode.
+        unsafe {
+            let device = vexDeviceGetByIndex(index as u32);
+            vexDeviceMotorBrakeModeSet(device, mode.into());
+            vexDeviceMotorVelocitySet(device, 0);
+        }
+    }
+
+    for (index, state) in DIGITAL_OUTS.iter().enumerate() {
+        let level = match state.load(Ordering::Relaxed) {
+            UNREGISTERED => continue,
+            state => state == encode_level(LogicLevel::High),
+        };
+
+        let expander_index = (index / ADI_PORTS) as u32;
+        let port_index = (index % ADI_PORTS) as u32;
+
+        if validate_port(expander_index as u8 + 1, SmartDeviceType::Adi).is_err() {
+            continue;
+        }
+
+        unsafe {
+            let device = vexDeviceGetByIndex(expander_index);
+            if vexDeviceAdiPortConfigGet(device, port_index)
+                == V5_AdiPortConfiguration::kAdiPortTypeDigitalOut
+            {
+                vexDeviceAdiValueSet(device, port_index, i32::from(level));
+            }
+        }
+    }
+}
+
+/// Stops every motor plugged into the brain, whether or not it has a registered safe state.
+///
+/// Motors are stopped by setting their voltage to zero, which lets them coast. Ports without a
+/// motor plugged into them are skipped. vexide's panic hook calls this before [`apply`] when
+/// `PanicBehavior::stop_motors` is enabled, so registered motors still end up in their safe state.
+pub fn stop_motors() {
+    for index in 0..SMART_PORTS {
+        if validate_port(index as u8 + 1, SmartDeviceType::Motor).is_err() {
+            continue;
+        }
+
+        unsafe {
+            vexDeviceMotorVoltageSet(vexDeviceGetByIndex(index as u32), 0);
+        }
+    }
+}
@@ -157,6 +157,38 @@ pub trait SmartDevice {
         }))
     }
 
+    /// Waits until VEXos processes a new packet from this device, returning the packet's timestamp.
+    ///
+    /// Devices send new data every [`SmartDevice::UPDATE_INTERVAL`]. Awaiting this in a loop allows
+    /// the loop to run exactly once per new reading, rather than sleeping and possibly reading the
+    /// same data twice (or skipping some).
+    ///
+    /// Only packets received after this method is called are counted. If the device is
+    /// disconnected, the returned future will not complete until it is plugged back in.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use vexide::prelude::*;
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let imu = InertialSensor::new(peripherals.port_1);
+    ///
+    ///     loop {
+    ///         imu.next_update().await;
+    ///
+    ///         if let Ok(heading) = imu.heading() {
+    ///             println!("Heading: {}", heading.as_degrees());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    fn next_update(&self) -> vexide_async::device::NextUpdate {
+        vexide_async::device::next_update(u32::from(self.port_number() - 1))
+    }
+
     /// Verify that the device type is currently plugged into this port, returning an appropriate
     /// [`PortError`] if not available.
     ///
@@ -76,7 +76,10 @@ use vex_sdk::{
 };
 
 use super::{PortError, SmartDevice, SmartDeviceType, SmartPort};
-use crate::math::{Angle, Direction};
+use crate::{
+    math::{Angle, Direction},
+    safe_state,
+};
 
 /// A motor plugged into a Smart Port.
 #[derive(Debug, PartialEq)]
@@ -341,6 +344,40 @@ impl Motor {
         self.set_target(MotorControl::Brake(mode))
     }
 
+    /// Registers the brake mode that the motor should be stopped with if the program panics or
+    /// crashes.
+    ///
+    /// The motor is braked with this mode by [`safe_state::apply`], which vexide's panic hook and
+    /// abort handler call before reporting an error. This replaces any safe state previously
+    /// registered on the motor's port, and stays registered until it is cleared with
+    /// [`Motor::clear_safe_state`] or the motor is converted back into a [`SmartPort`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use vexide::{prelude::*, smart::motor::BrakeMode};
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let lift = Motor::new(peripherals.port_1, Gearset::Red, Direction::Forward);
+    ///
+    ///     // Hold the lift in place if the program crashes, rather than letting it fall.
+    ///     lift.set_safe_state(BrakeMode::Hold);
+    /// }
+    /// ```
+    ///
+    /// [`safe_state::apply`]: crate::safe_state::apply
+    pub fn set_safe_state(&self, mode: BrakeMode) {
+        safe_state::set_motor(self.port.number(), Some(mode));
+    }
+
+    /// Removes the motor's safe state, so that it isn't braked if the program panics or crashes.
+    ///
+    /// See [`Motor::set_safe_state`] for more information.
+    pub fn clear_safe_state(&self) {
+        safe_state::set_motor(self.port.number(), None);
+    }
+
     /// Sets an absolute position target for the motor to attempt to reach.
     ///
     /// # Errors
@@ -1586,6 +1623,7 @@ impl SmartDevice for Motor {
 }
 impl From<Motor> for SmartPort {
     fn from(device: Motor) -> Self {
+        device.clear_safe_state();
         device.port
     }
 }
@@ -87,11 +87,20 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
         quote! {}
     };
 
+    let panic_behavior = if let Some(behavior) = opts.panic_behavior {
+        quote! {
+            ::vexide::startup::set_panic_behavior(#behavior);
+        }
+    } else {
+        quote! {}
+    };
+
     quote! {
         fn main() -> #ret_type {
             unsafe {
                 ::vexide::startup::startup();
             }
+            #panic_behavior
 
             #banner_print
             #inner
@@ -119,6 +128,8 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
 /// - `banner`: Allows for disabling or using a custom banner theme. When `enabled = false` the
 ///   banner will be disabled. `theme` can be set to a custom `BannerTheme` struct.
 /// - `code_sig`: Allows using a custom `CodeSignature` struct to configure program behavior.
+/// - `panic`: Allows using a custom `PanicBehavior` struct to configure what happens when the
+///   program panics. This requires the `panic-hook` feature.
 ///
 /// # Examples
 ///
@@ -178,6 +189,19 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
 ///     println!("Hello world!")
 /// }
 /// ```
+///
+/// The behavior of the program when it panics can also be changed, such as to stop every motor:
+///
+/// ```
+/// use vexide::{prelude::*, startup::PanicBehavior};
+///
+/// static PANIC_BEHAVIOR: PanicBehavior = PanicBehavior::new().stop_motors(true);
+///
+/// #[vexide::main(panic = PANIC_BEHAVIOR)]
+/// async fn main(_p: Peripherals) {
+///     panic!("Stopping motors!")
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn main(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as ItemFn);
@@ -308,6 +332,7 @@ mod test {
                 banner_enabled: false,
                 banner_theme: None,
                 code_sig: None,
+                panic_behavior: None,
             },
         );
         assert!(!entrypoint.to_string().contains("banner"));
@@ -318,6 +343,7 @@ mod test {
                 banner_enabled: true,
                 banner_theme: None,
                 code_sig: None,
+                panic_behavior: None,
             },
         );
         assert!(entrypoint.to_string().contains("banner"));
@@ -332,6 +358,7 @@ mod test {
                 "__custom_code_sig_ident__",
                 proc_macro2::Span::call_site(),
             )),
+            panic_behavior: None,
         });
 
         assert!(code_sig.to_string().contains(
@@ -339,6 +366,35 @@ mod test {
         ));
     }
 
+    #[test]
+    fn sets_panic_behavior_from_parsed_opts() {
+        let source = quote! {
+            async fn main(_peripherals: Peripherals) {
+                println!("Hello, world!");
+            }
+        };
+        let input = syn::parse2::<ItemFn>(source).unwrap();
+
+        let entrypoint = make_entrypoint(&input, MacroOpts::default());
+        assert!(!entrypoint.to_string().contains("set_panic_behavior"));
+
+        let entrypoint = make_entrypoint(
+            &input,
+            MacroOpts {
+                panic_behavior: Some(Ident::new(
+                    "__custom_panic_behavior__",
+                    proc_macro2::Span::call_site(),
+                )),
+                ..MacroOpts::default()
+            },
+        );
+        assert!(
+            entrypoint.to_string().contains(
+                ":: vexide :: startup :: set_panic_behavior (__custom_panic_behavior__) ;"
+            )
+        );
+    }
+
     #[test]
     fn requires_async() {
         let source = quote! {
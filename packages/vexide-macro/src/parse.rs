@@ -16,6 +16,8 @@ mod kw {
     custom_keyword!(theme);
 
     custom_keyword!(code_sig);
+
+    custom_keyword!(panic);
 }
 
 #[derive(Clone)]
@@ -23,6 +25,7 @@ pub struct MacroOpts {
     pub banner_enabled: bool,
     pub banner_theme: Option<Ident>,
     pub code_sig: Option<Ident>,
+    pub panic_behavior: Option<Ident>,
 }
 
 impl Default for MacroOpts {
@@ -31,6 +34,7 @@ impl Default for MacroOpts {
             banner_enabled: true,
             banner_theme: None,
             code_sig: None,
+            panic_behavior: None,
         }
     }
 }
@@ -53,6 +57,7 @@ impl From<Attrs> for MacroOpts {
                     }
                 }
                 Attribute::CodeSig(code_sig) => opts.code_sig = Some(code_sig.into_ident()),
+                Attribute::Panic(panic) => opts.panic_behavior = Some(panic.into_ident()),
             }
         }
         opts
@@ -74,6 +79,7 @@ impl Parse for Attrs {
 pub enum Attribute {
     Banner(Banner),
     CodeSig(CodeSig),
+    Panic(Panic),
 }
 
 impl Parse for Attribute {
@@ -83,6 +89,8 @@ impl Parse for Attribute {
             input.parse().map(Attribute::Banner)
         } else if lookahead.peek(kw::code_sig) {
             input.parse().map(Attribute::CodeSig)
+        } else if lookahead.peek(kw::panic) {
+            input.parse().map(Attribute::Panic)
         } else {
             Err(lookahead.error())
         }
@@ -230,6 +238,36 @@ impl ToTokens for CodeSig {
     }
 }
 
+pub struct Panic {
+    token: kw::panic,
+    eq: Token![=],
+    ident: Ident,
+}
+
+impl Panic {
+    pub fn into_ident(self) -> Ident {
+        self.ident
+    }
+}
+
+impl Parse for Panic {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        Ok(Self {
+            token: input.parse()?,
+            eq: input.parse()?,
+            ident: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Panic {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.token.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use quote::quote;
@@ -267,6 +305,16 @@ mod test {
         assert_eq!(input.into_ident(), ident);
     }
 
+    #[test]
+    fn parses_panic_attribute() {
+        let ident = Ident::new("MY_PANIC_BEHAVIOR", proc_macro2::Span::call_site());
+        let source = quote! {
+            panic = #ident
+        };
+        let input = syn::parse2::<Panic>(source).unwrap();
+        assert_eq!(input.into_ident(), ident);
+    }
+
     #[test]
     fn parses_attrs_into_macro_opts() {
         let source = quote! {
@@ -277,6 +325,16 @@ mod test {
         let opts = MacroOpts::from(input);
         assert!(opts.banner_enabled);
         assert_eq!(opts.code_sig.unwrap().to_string(), "my_code_sig");
+        assert_eq!(opts.panic_behavior, None);
+
+        let source = quote! {
+            code_sig = my_code_sig, panic = MY_PANIC_BEHAVIOR
+        };
+        let opts = MacroOpts::from(syn::parse2::<Attrs>(source).unwrap());
+        assert_eq!(
+            opts.panic_behavior.unwrap().to_string(),
+            "MY_PANIC_BEHAVIOR"
+        );
     }
 
     #[test]
@@ -89,6 +89,16 @@ impl Display for FaultException {
 }
 
 impl Fault {
+    /// Returns the values of registers r0 through r12, sp, lr, and pc, in that order.
+    pub fn register_file(&self) -> [u32; 16] {
+        let mut registers = [0; 16];
+        registers[..13].copy_from_slice(&self.registers);
+        registers[13] = self.stack_pointer;
+        registers[14] = self.link_register;
+        registers[15] = self.program_counter;
+        registers
+    }
+
     pub fn address(&self) -> u32 {
         let address: u32;
 
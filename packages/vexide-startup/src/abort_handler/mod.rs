@@ -216,10 +216,17 @@ pub unsafe extern "C" fn fault_exception_handler(fault: *const Fault) -> ! {
         }
     }
 
+    // Put any devices with a registered safe state into it.
+    vexide_devices::safe_state::apply();
+
     let fault = unsafe { *fault };
 
     report::report_fault(&fault);
 
+    // Save the report after it's been shown, in case writing to the SD card fails or hangs.
+    #[cfg(feature = "crash-report")]
+    report::save_fault(&fault);
+
     let mut prev_touch_event = V5_TouchEvent::kTouchEventRelease;
     loop {
         let mut status = V5_TouchStatus::default();
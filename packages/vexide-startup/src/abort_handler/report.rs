@@ -2,8 +2,14 @@ use std::fmt::{self, Write};
 
 #[cfg(all(target_os = "vexos", feature = "backtrace"))]
 use vex_libunwind::UnwindCursor;
+#[cfg(all(target_os = "vexos", feature = "backtrace"))]
+use vexide_core::backtrace::SymbolTable;
 
 use super::fault::Fault;
+#[cfg(feature = "crash-report")]
+use super::fault::FaultException;
+#[cfg(feature = "crash-report")]
+use crate::crash_report::{self, CrashKind};
 use crate::error_report::ErrorReport;
 #[cfg(all(target_os = "vexos", feature = "backtrace"))]
 use crate::error_report::backtrace::BacktraceIter;
@@ -76,23 +82,22 @@ pub fn report_fault(fault: &Fault) {
         fault.stack_pointer, fault.link_register, fault.program_counter
     );
 
-    dialog.write_registers({
-        let mut arr = [0u32; 16];
-        arr[..13].copy_from_slice(&fault.registers);
-        arr[13] = fault.stack_pointer;
-        arr[14] = fault.link_register;
-        arr[15] = fault.program_counter;
-        arr
-    });
+    dialog.write_registers(fault.register_file());
 
     #[cfg(all(target_os = "vexos", feature = "backtrace"))]
     if let Ok(cursor) = UnwindCursor::new(&unsafe { fault.unwind_context() }) {
         _ = writeln!(dialog, "stack backtrace (check terminal):");
         dialog.write_backtrace(BacktraceIter::new(cursor.clone()));
 
+        let symbols = SymbolTable::current();
+
         _ = writeln!(serial, "stack backtrace:");
         for (i, frame) in BacktraceIter::new(cursor).enumerate() {
-            _ = writeln!(serial, "{i:>3}: 0x{frame:x}");
+            _ = write!(serial, "{i:>3}: 0x{frame:x}");
+            if let Some(symbol) = symbols.and_then(|table| table.lookup(frame as usize)) {
+                _ = write!(serial, " - {symbol}");
+            }
+            _ = writeln!(serial);
         }
     }
 
@@ -128,3 +133,62 @@ pub fn report_fault(fault: &Fault) {
 
     serial.flush();
 }
+
+/// Saves a report of the fault to the SD card.
+///
+/// This runs inside the fault handler, so it doesn't allocate or use `std::fs`. The code that
+/// faulted may have been holding the allocator's lock, or may have corrupted the heap.
+#[cfg(feature = "crash-report")]
+pub fn save_fault(fault: &Fault) {
+    /// The most backtrace frames that are saved.
+    const MAX_FRAMES: usize = 32;
+
+    /// The longest message that is saved, in bytes.
+    const MAX_MESSAGE_LEN: usize = 256;
+
+    let kind = match fault.exception {
+        FaultException::UndefinedInstruction => CrashKind::UndefinedInstruction,
+        FaultException::PrefetchAbort => CrashKind::PrefetchAbort,
+        FaultException::DataAbort => CrashKind::DataAbort,
+    };
+
+    let mut frames = [core::ptr::null(); MAX_FRAMES];
+    let frame_count = capture_frames(fault, &mut frames);
+
+    let mut bytes = [0; 1024];
+    let Some(len) = crash_report::encode_into(
+        &mut bytes,
+        kind,
+        vexide_core::time::user_uptime(),
+        Some(fault.register_file()),
+        format_args!(
+            "{} exception at 0x{:x}:\n{fault}",
+            fault.exception, fault.program_counter
+        ),
+        MAX_MESSAGE_LEN,
+        &frames[..frame_count],
+    ) else {
+        return;
+    };
+
+    crash_report::save_raw(&bytes[..len]);
+}
+
+/// Fills `frames` with the backtrace of the fault, returning how many frames were captured.
+#[cfg(all(feature = "crash-report", feature = "backtrace"))]
+fn capture_frames(fault: &Fault, frames: &mut [*const ()]) -> usize {
+    let Ok(cursor) = UnwindCursor::new(&unsafe { fault.unwind_context() }) else {
+        return 0;
+    };
+
+    frames
+        .iter_mut()
+        .zip(BacktraceIter::new(cursor))
+        .map(|(slot, frame)| *slot = frame as usize as *const ())
+        .count()
+}
+
+#[cfg(all(feature = "crash-report", not(feature = "backtrace")))]
+const fn capture_frames(_fault: &Fault, _frames: &mut [*const ()]) -> usize {
+    0
+}
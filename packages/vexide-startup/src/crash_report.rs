@@ -0,0 +1,494 @@
+//! Crash reports saved to the SD card.
+//!
+//! When the `crash-report` feature is enabled, the panic hook and abort handler save a report of
+//! each crash to the SD card, in addition to showing it on the screen. This allows a crash to be
+//! inspected after the program has been restarted, such as after a match. The report from the
+//! previous run of the program is loaded by [`startup`](crate::startup), and can be retrieved with
+//! [`last_crash`].
+//!
+//! Reports are only saved if an SD card is inserted.
+
+use std::{fmt, fs, sync::OnceLock, time::Duration};
+
+use vexide_core::backtrace::Backtrace;
+
+/// The file on the SD card that crash reports are saved to.
+pub const CRASH_REPORT_PATH: &str = "vexide_crash.bin";
+
+const MAGIC: [u8; 4] = *b"VXCR";
+const VERSION: u8 = 2;
+
+/// The encoded crash report loaded from the SD card when the program started.
+///
+/// This is stored encoded, since [`Backtrace`] can't be shared between threads.
+static LAST_CRASH: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+
+/// What caused a crash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CrashKind {
+    /// The program panicked.
+    Panic,
+
+    /// The CPU tried to run an invalid instruction.
+    UndefinedInstruction,
+
+    /// The CPU failed to fetch an instruction.
+    PrefetchAbort,
+
+    /// The CPU failed to read or write memory.
+    DataAbort,
+}
+
+impl CrashKind {
+    const fn to_byte(self) -> u8 {
+        match self {
+            Self::Panic => 0,
+            Self::UndefinedInstruction => 1,
+            Self::PrefetchAbort => 2,
+            Self::DataAbort => 3,
+        }
+    }
+
+    const fn from_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0 => Self::Panic,
+            1 => Self::UndefinedInstruction,
+            2 => Self::PrefetchAbort,
+            3 => Self::DataAbort,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for CrashKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Panic => "Panic",
+            Self::UndefinedInstruction => "Undefined Instruction",
+            Self::PrefetchAbort => "Prefetch Abort",
+            Self::DataAbort => "Data Abort",
+        })
+    }
+}
+
+/// A report of a crash that was saved to the SD card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CrashReport {
+    /// What caused the crash.
+    pub kind: CrashKind,
+
+    /// The panic message, or a description of the CPU fault.
+    pub message: String,
+
+    /// How long the program had been running for when it crashed.
+    pub uptime: Duration,
+
+    /// The values of registers r0 through r12, sp, lr, and pc at the time of a CPU fault.
+    ///
+    /// This is [`None`] for panics.
+    pub registers: Option<[u32; 16]>,
+
+    /// The backtrace of the crash, which is empty if one couldn't be captured.
+    pub backtrace: Backtrace,
+}
+
+impl CrashReport {
+    #[cfg(feature = "panic-hook")]
+    pub(crate) fn new(kind: CrashKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            uptime: vexide_core::time::user_uptime(),
+            registers: None,
+            backtrace: std::iter::empty().collect(),
+        }
+    }
+
+    #[cfg(any(test, feature = "panic-hook"))]
+    fn encode(&self) -> Vec<u8> {
+        // Every varint in the backtrace takes at most 10 bytes.
+        let max_backtrace_len = 29 + (self.backtrace.frames().len() + 1) * 10;
+        let mut bytes = vec![0; HEADER_SIZE + self.message.len() + max_backtrace_len];
+
+        let len = encode_into(
+            &mut bytes,
+            self.kind,
+            self.uptime,
+            self.registers,
+            format_args!("{}", self.message),
+            usize::MAX,
+            self.backtrace.frames(),
+        )
+        .expect("buffer is large enough for the report");
+        bytes.truncate(len);
+        bytes
+    }
+
+    fn decode(mut bytes: &[u8]) -> Option<Self> {
+        fn take<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+            let (taken, rest) = bytes.split_first_chunk::<N>()?;
+            *bytes = rest;
+            Some(*taken)
+        }
+
+        fn take_u32(bytes: &mut &[u8]) -> Option<u32> {
+            take(bytes).map(u32::from_le_bytes)
+        }
+
+        if take::<4>(&mut bytes)? != MAGIC || take::<1>(&mut bytes)? != [VERSION] {
+            return None;
+        }
+
+        let [kind] = take(&mut bytes)?;
+        let kind = CrashKind::from_byte(kind)?;
+        let uptime = Duration::from_millis(take_u32(&mut bytes)?.into());
+
+        let registers = match take(&mut bytes)? {
+            [0] => None,
+            [1] => {
+                let mut registers = [0; 16];
+                for register in &mut registers {
+                    *register = take_u32(&mut bytes)?;
+                }
+                Some(registers)
+            }
+            _ => return None,
+        };
+
+        let message_len = take_u32(&mut bytes)? as usize;
+        let (message, mut bytes) = bytes.split_at_checked(message_len)?;
+        let message = String::from_utf8_lossy(message).into_owned();
+
+        let backtrace_len = take_u32(&mut bytes)? as usize;
+        let (backtrace, _) = bytes.split_at_checked(backtrace_len)?;
+        let backtrace = Backtrace::decode(backtrace).ok()?.backtrace;
+
+        Some(Self {
+            kind,
+            message,
+            uptime,
+            registers,
+            backtrace,
+        })
+    }
+
+    /// Saves the report to the SD card, replacing the previous one.
+    ///
+    /// Errors are ignored, since there's nothing else that can be done about them while crashing.
+    #[cfg(feature = "panic-hook")]
+    pub(crate) fn save(&self) {
+        _ = fs::write(CRASH_REPORT_PATH, self.encode());
+    }
+}
+
+/// Saves an encoded report to the SD card, replacing the previous one.
+///
+/// Unlike [`CrashReport::save`], this calls the SDK's file functions directly rather than going
+/// through `std::fs`, so it can be used while handling a CPU fault, when the heap may be corrupted
+/// or locked. Nothing is saved if the file can't be opened, such as when there's no SD card.
+#[cfg(all(target_os = "vexos", feature = "abort-handler"))]
+pub(crate) fn save_raw(bytes: &[u8]) {
+    unsafe {
+        let file = vex_sdk::vexFileOpenCreate(CRASH_REPORT_PATH_C.as_ptr());
+        if file.is_null() {
+            return;
+        }
+
+        vex_sdk::vexFileWrite(
+            bytes.as_ptr().cast_mut().cast(),
+            1,
+            bytes.len() as u32,
+            file,
+        );
+        vex_sdk::vexFileClose(file);
+    }
+}
+
+/// [`CRASH_REPORT_PATH`] as a C string, for use with the SDK's file functions.
+#[cfg(any(test, all(target_os = "vexos", feature = "abort-handler")))]
+const CRASH_REPORT_PATH_C: &std::ffi::CStr = c"vexide_crash.bin";
+
+/// The size of an encoded report, excluding its message and backtrace.
+const HEADER_SIZE: usize = 4 + 1 + 1 + 4 + 1 + 16 * 4 + 4 + 4;
+
+/// Encodes a report into `out` without allocating, returning the number of bytes written.
+///
+/// The message is truncated to `max_message_len` bytes, so that there's space left for the
+/// backtrace. [`None`] is returned if the report doesn't fit in `out`.
+pub(crate) fn encode_into(
+    out: &mut [u8],
+    kind: CrashKind,
+    uptime: Duration,
+    registers: Option<[u32; 16]>,
+    message: fmt::Arguments<'_>,
+    max_message_len: usize,
+    frames: &[*const ()],
+) -> Option<usize> {
+    let mut writer = Writer {
+        bytes: out,
+        len: 0,
+        limit: usize::MAX,
+    };
+    writer.write(&MAGIC)?;
+    writer.write(&[VERSION, kind.to_byte()])?;
+    writer.write(&(uptime.as_millis() as u32).to_le_bytes())?;
+
+    match registers {
+        Some(registers) => {
+            writer.write(&[1])?;
+            for register in registers {
+                writer.write(&register.to_le_bytes())?;
+            }
+        }
+        None => writer.write(&[0])?,
+    }
+
+    // The message's length is filled in once it's been written.
+    let message_len_offset = writer.len;
+    writer.write(&[0; 4])?;
+    let message_start = writer.len;
+    writer.limit = message_start.saturating_add(max_message_len);
+    _ = fmt::Write::write_fmt(&mut writer, message);
+    writer.limit = usize::MAX;
+    let message_len = (writer.len - message_start) as u32;
+    writer.bytes[message_len_offset..message_start].copy_from_slice(&message_len.to_le_bytes());
+
+    let backtrace_len_offset = writer.len;
+    writer.write(&[0; 4])?;
+    let backtrace_len = Backtrace::encode_frames_into(frames, &mut writer.bytes[writer.len..])?;
+    writer.bytes[backtrace_len_offset..backtrace_len_offset + 4]
+        .copy_from_slice(&(backtrace_len as u32).to_le_bytes());
+
+    Some(writer.len + backtrace_len)
+}
+
+/// Writes to a fixed-size buffer, so that reports can be encoded without allocating.
+struct Writer<'a> {
+    bytes: &'a mut [u8],
+    len: usize,
+
+    /// The length that text written with [`fmt::Write`] is truncated to.
+    limit: usize,
+}
+
+impl Writer<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Option<()> {
+        let end = self.len.checked_add(bytes.len())?;
+        self.bytes.get_mut(self.len..end)?.copy_from_slice(bytes);
+        self.len = end;
+        Some(())
+    }
+}
+
+impl fmt::Write for Writer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let space = self.limit.min(self.bytes.len()).saturating_sub(self.len);
+        let end = s.floor_char_boundary(space);
+        self.write(&s.as_bytes()[..end]).ok_or(fmt::Error)?;
+
+        // Stop formatting once the text has been truncated.
+        if end == s.len() {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        }
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} after {:.3}s:", self.kind, self.uptime.as_secs_f64())?;
+        write!(f, "{}", self.message)?;
+
+        if let Some(registers) = self.registers {
+            writeln!(f, "\n\nregisters at time of fault:")?;
+            for (i, register) in registers.iter().enumerate() {
+                match i {
+                    0..=9 => write!(f, " r{i}: 0x{register:x}")?,
+                    10..=12 => write!(f, "r{i}: 0x{register:x}")?,
+                    13 => write!(f, " sp: 0x{register:x}")?,
+                    14 => write!(f, " lr: 0x{register:x}")?,
+                    _ => write!(f, " pc: 0x{register:x}")?,
+                }
+                if i != registers.len() - 1 {
+                    writeln!(f)?;
+                }
+            }
+        }
+
+        if !self.backtrace.frames().is_empty() {
+            write!(f, "\n\n{}", self.backtrace)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the report saved by the previous run of the program, then clears it so that it isn't
+/// reported again.
+pub(crate) fn load_last_crash() {
+    LAST_CRASH.get_or_init(|| {
+        let report = fs::read(CRASH_REPORT_PATH)
+            .ok()
+            .filter(|bytes| CrashReport::decode(bytes).is_some());
+
+        if report.is_some() {
+            _ = fs::write(CRASH_REPORT_PATH, []);
+        }
+
+        report
+    });
+}
+
+/// Returns the report of the crash that ended the previous run of the program, if there was one.
+///
+/// The report is read from the SD card by [`startup`](crate::startup) and then removed from the
+/// card, so each crash is only reported once. If the previous run didn't crash, this returns
+/// [`None`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use vexide::prelude::*;
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     if let Some(crash) = vexide::startup::last_crash() {
+///         println!("The last run of this program crashed:\n{crash}");
+///     }
+/// }
+/// ```
+#[must_use]
+pub fn last_crash() -> Option<CrashReport> {
+    CrashReport::decode(LAST_CRASH.get()?.as_deref()?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::iter;
+
+    use vexide_core::program::{CodeSignature, ProgramOptions, ProgramOwner, ProgramType};
+
+    use super::*;
+
+    // Normally defined by `#[vexide::main]`, and included in encoded backtraces.
+    #[unsafe(no_mangle)]
+    static __VEXIDE_CODE_SIGNATURE: CodeSignature = CodeSignature::new(
+        ProgramType::User,
+        ProgramOwner::Partner,
+        ProgramOptions::empty(),
+    );
+
+    fn fault_report() -> CrashReport {
+        CrashReport {
+            kind: CrashKind::DataAbort,
+            message: "Data Abort exception at 0x3800123".to_string(),
+            uptime: Duration::from_millis(61_250),
+            registers: Some(core::array::from_fn(|i| i as u32 * 0x100)),
+            backtrace: [0x0380_217b, 0x0380_209b, 0x0370_0000]
+                .into_iter()
+                .map(|frame: usize| frame as *const ())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let report = fault_report();
+        assert_eq!(CrashReport::decode(&report.encode()), Some(report));
+    }
+
+    #[test]
+    fn round_trip_without_backtrace() {
+        let report = CrashReport {
+            kind: CrashKind::Panic,
+            message: "panicked at src/main.rs:5:5:\nOops!".to_string(),
+            uptime: Duration::from_secs(3),
+            registers: None,
+            backtrace: iter::empty().collect(),
+        };
+        assert_eq!(CrashReport::decode(&report.encode()), Some(report));
+    }
+
+    #[test]
+    fn rejects_truncated_reports() {
+        let bytes = fault_report().encode();
+
+        for len in 0..bytes.len() {
+            assert_eq!(
+                CrashReport::decode(&bytes[..len]),
+                None,
+                "decoded {len} of {} bytes",
+                bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn encodes_into_fixed_buffers() {
+        let report = fault_report();
+        let expected = report.encode();
+
+        let mut buffer = [0; 256];
+        let len = encode_into(
+            &mut buffer,
+            report.kind,
+            report.uptime,
+            report.registers,
+            format_args!("{}", report.message),
+            usize::MAX,
+            report.backtrace.frames(),
+        )
+        .unwrap();
+        assert_eq!(&buffer[..len], expected);
+
+        assert_eq!(
+            encode_into(
+                &mut buffer[..expected.len() - 1],
+                report.kind,
+                report.uptime,
+                report.registers,
+                format_args!("{}", report.message),
+                usize::MAX,
+                report.backtrace.frames(),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn truncates_long_messages() {
+        let report = fault_report();
+
+        let mut buffer = [0; 256];
+        let len = encode_into(
+            &mut buffer,
+            report.kind,
+            report.uptime,
+            report.registers,
+            format_args!("{}", report.message),
+            10,
+            report.backtrace.frames(),
+        )
+        .unwrap();
+
+        let decoded = CrashReport::decode(&buffer[..len]).unwrap();
+        assert_eq!(decoded.message, report.message[..10]);
+        assert_eq!(decoded.backtrace, report.backtrace);
+    }
+
+    #[test]
+    fn c_path_matches() {
+        assert_eq!(CRASH_REPORT_PATH_C.to_str(), Ok(CRASH_REPORT_PATH));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = fault_report().encode();
+        bytes[4] = VERSION - 1;
+
+        assert_eq!(CrashReport::decode(&bytes), None);
+    }
+}
@@ -101,6 +101,12 @@ impl ErrorReport {
         unsafe {
             vex_sdk::vexDisplayTextSize(1, 5);
         }
+
+        if let Some(symbols) = vexide_core::backtrace::SymbolTable::current() {
+            self.write_symbolized_backtrace(trace, symbols);
+            return;
+        }
+
         let mut i = 0;
         for frame in trace {
             let format = c"  %d: 0x%08x";
@@ -135,6 +141,57 @@ impl ErrorReport {
     }
 }
 
+impl ErrorReport {
+    /// The longest function name that will be shown in a symbolized backtrace.
+    #[cfg(all(target_os = "vexos", feature = "backtrace"))]
+    const SYMBOL_MAX_WIDTH: usize = 56;
+
+    /// Writes a backtrace with one frame per line, including the name of each frame's function.
+    ///
+    /// Frames that don't fit in the box are left out.
+    #[cfg(all(target_os = "vexos", feature = "backtrace"))]
+    fn write_symbolized_backtrace(
+        &mut self,
+        trace: impl Iterator<Item = u32>,
+        symbols: vexide_core::backtrace::SymbolTable<'_>,
+    ) {
+        let bottom = Self::DISPLAY_HEIGHT - Self::BOX_MARGIN - Self::BOX_PADDING;
+
+        for (i, frame) in trace.enumerate() {
+            if self.y_offset + 10 > bottom {
+                break;
+            }
+
+            unsafe {
+                match symbols.lookup(frame as usize) {
+                    Some(symbol) => vex_sdk::vexDisplayPrintf(
+                        50,
+                        self.y_offset,
+                        0,
+                        c"%2d: %.*s+0x%x".as_ptr(),
+                        i,
+                        symbol.name().len().min(Self::SYMBOL_MAX_WIDTH) as i32,
+                        symbol.name().as_ptr(),
+                        symbol.offset(),
+                    ),
+                    None => vex_sdk::vexDisplayPrintf(
+                        50,
+                        self.y_offset,
+                        0,
+                        c"%2d: 0x%08x".as_ptr(),
+                        i,
+                        frame,
+                    ),
+                }
+            }
+
+            self.y_offset += 10;
+        }
+
+        self.y_offset += 10;
+    }
+}
+
 impl Write for ErrorReport {
     fn write_str(&mut self, s: &str) -> std::fmt::Result {
         unsafe {
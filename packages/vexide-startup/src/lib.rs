@@ -53,6 +53,8 @@ pub mod banner;
 
 #[cfg(all(target_os = "vexos", feature = "abort-handler"))]
 mod abort_handler;
+#[cfg(feature = "crash-report")]
+mod crash_report;
 mod error_report;
 #[cfg(feature = "panic-hook")]
 mod panic_hook;
@@ -60,6 +62,11 @@ mod panic_hook;
 mod patcher;
 mod sdk;
 
+#[cfg(feature = "crash-report")]
+pub use crash_report::{CRASH_REPORT_PATH, CrashKind, CrashReport, last_crash};
+#[cfg(feature = "panic-hook")]
+pub use panic_hook::{PanicBehavior, set_panic_behavior};
+
 // Linkerscript Symbols
 //
 // All of these external symbols are defined by either Rust's armv7a-vex-v5 linkerscript, our ours
@@ -146,7 +153,9 @@ unsafe extern "C" fn _vexide_boot() {
 ///   restarts the program if necessary.
 /// - Registers a custom [panic hook] to allow panic messages to be drawn to the screen and
 ///   backtrace to be collected. This can be enabled/disabled using the `panic-hook` and `backtrace`
-///   features.
+///   features. What the hook does after a panic can be changed with [`set_panic_behavior`].
+/// - Loads the report of the previous run's crash from the SD card, which can be retrieved with
+///   [`last_crash`], if the `crash-report` feature is specified.
 ///
 /// [differential upload patches]: https://vexide.dev/docs/building-uploading/#uploading-strategies
 /// [panic hook]: https://doc.rust-lang.org/std/panic/fn.set_hook.html
@@ -190,6 +199,10 @@ pub unsafe fn startup() {
         abort_handler::install_vector_table();
     }
 
+    // Load the previous crash report before a new one can overwrite it.
+    #[cfg(feature = "crash-report")]
+    crash_report::load_last_crash();
+
     // Register custom panic hook if needed.
     #[cfg(feature = "panic-hook")]
     std::panic::set_hook(Box::new(panic_hook::hook));
@@ -3,45 +3,185 @@
 //! This extends the default `libstd` panic handler with support for capturing backtrace data and
 //! drawing the panic message to the display screen.
 
-use std::{fmt::Write, panic::PanicHookInfo};
+use std::{
+    fmt::Write,
+    panic::PanicHookInfo,
+    sync::{Mutex, PoisonError},
+};
 
-#[cfg(all(target_os = "vexos", feature = "backtrace"))]
-use vex_libunwind::{UnwindContext, UnwindCursor};
+use vexide_core::backtrace::Backtrace;
 
+#[cfg(feature = "crash-report")]
+use crate::crash_report::{CrashKind, CrashReport};
 use crate::error_report::ErrorReport;
-#[cfg(all(target_os = "vexos", feature = "backtrace"))]
-use crate::error_report::backtrace::BacktraceIter;
+
+/// The behavior used by [`hook`], set with [`set_panic_behavior`].
+static BEHAVIOR: Mutex<PanicBehavior> = Mutex::new(PanicBehavior::DEFAULT);
+
+/// Configures what vexide's panic hook does when the program panics.
+///
+/// By default, the panic message and a backtrace are printed to the terminal and drawn to the
+/// display, and then the program stops running user code while keeping the brain responsive. Each
+/// part of this can be changed:
+///
+/// - [`stop_motors`](Self::stop_motors) stops every motor before the panic is reported.
+/// - [`show_report`](Self::show_report) controls whether the error box is drawn to the display,
+///   which can be turned off to draw a custom screen instead.
+/// - [`on_panic`](Self::on_panic) calls a function with the panic's information and backtrace,
+///   after the panic has been reported.
+///
+/// The panic message is always printed to the terminal.
+///
+/// There is no option to keep the program alive and restart the competition runtime after a
+/// panic. vexide programs are built with `panic = "abort"`, so the panicking task's stack can't be
+/// unwound, and nothing that it or the async executor owned can be cleaned up or safely run again.
+/// Once the hook returns, the program stops running user code.
+///
+/// This type is used with [`set_panic_behavior`] or the `panic` parameter of `#[vexide::main]`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::panic::PanicHookInfo;
+///
+/// use vexide::{backtrace::Backtrace, prelude::*, startup::PanicBehavior};
+///
+/// fn log_panic(info: &PanicHookInfo<'_>, backtrace: &Backtrace) {
+///     // Send the panic to a radio, log it to the SD card, draw a custom screen, etc.
+/// }
+///
+/// static PANIC_BEHAVIOR: PanicBehavior =
+///     PanicBehavior::new().stop_motors(true).on_panic(log_panic);
+///
+/// #[vexide::main(panic = PANIC_BEHAVIOR)]
+/// async fn main(_peripherals: Peripherals) {
+///     panic!("Oops!");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PanicBehavior {
+    stop_motors: bool,
+    show_report: bool,
+    on_panic: Option<fn(&PanicHookInfo<'_>, &Backtrace)>,
+}
+
+impl PanicBehavior {
+    /// The behavior used if none is set, which is the same as [`PanicBehavior::new`].
+    pub const DEFAULT: Self = Self::new();
+
+    /// Creates the default panic behavior, which reports the panic and then stops.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            stop_motors: false,
+            show_report: true,
+            on_panic: None,
+        }
+    }
+
+    /// Sets whether every motor is stopped as soon as the program panics.
+    ///
+    /// Motors keep running at their last set voltage or velocity by default, unless they have a
+    /// safe state registered with `Motor::set_safe_state`. When enabled, every motor is stopped
+    /// with `safe_state::stop_motors` before registered safe states are applied.
+    #[must_use]
+    pub const fn stop_motors(mut self, stop_motors: bool) -> Self {
+        self.stop_motors = stop_motors;
+        self
+    }
+
+    /// Sets whether the panic message and backtrace are drawn to the display.
+    ///
+    /// This can be disabled so that [`on_panic`](Self::on_panic) can draw its own screen.
+    #[must_use]
+    pub const fn show_report(mut self, show_report: bool) -> Self {
+        self.show_report = show_report;
+        self
+    }
+
+    /// Sets a function that is called with information about the panic and a backtrace of where it
+    /// happened.
+    ///
+    /// This is called after the panic has been reported (and saved to the SD card, if the
+    /// `crash-report` feature is enabled).
+    #[must_use]
+    pub const fn on_panic(mut self, on_panic: fn(&PanicHookInfo<'_>, &Backtrace)) -> Self {
+        self.on_panic = Some(on_panic);
+        self
+    }
+}
+
+impl Default for PanicBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sets what vexide's panic hook does when the program panics.
+///
+/// This is also done by the `panic` parameter of `#[vexide::main]`. See [`PanicBehavior`] for the
+/// available options.
+///
+/// # Examples
+///
+/// ```no_run
+/// use vexide::{prelude::*, startup::PanicBehavior};
+///
+/// #[vexide::main]
+/// async fn main(_peripherals: Peripherals) {
+///     vexide::startup::set_panic_behavior(PanicBehavior::new().stop_motors(true));
+/// }
+/// ```
+pub fn set_panic_behavior(behavior: PanicBehavior) {
+    *BEHAVIOR.lock().unwrap_or_else(PoisonError::into_inner) = behavior;
+}
 
 /// Panic hook for vexide programs.
 ///
 /// This extends the default `libstd` panic handler with support for capturing backtrace data and
 /// drawing the panic message to the display screen.
 pub(crate) fn hook(info: &PanicHookInfo<'_>) {
-    let mut dialog = ErrorReport::begin();
+    let behavior = *BEHAVIOR.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if behavior.stop_motors {
+        vexide_devices::safe_state::stop_motors();
+    }
+
+    // Put any devices with a registered safe state into it before reporting the panic.
+    vexide_devices::safe_state::apply();
+
+    let mut dialog = behavior.show_report.then(ErrorReport::begin);
 
     eprintln!("{info}");
-    writeln!(dialog, "{info}").unwrap();
+    if let Some(dialog) = &mut dialog {
+        writeln!(dialog, "{info}").unwrap();
+    }
 
-    #[cfg(all(target_os = "vexos", feature = "backtrace"))]
-    {
-        _ = UnwindContext::capture(|context| {
-            let cursor = UnwindCursor::new(&context)?;
+    // Captured once so that the screen, the terminal, the crash report, and `on_panic` all show
+    // the same frames.
+    let backtrace = Backtrace::capture();
 
+    if !backtrace.frames().is_empty() {
+        #[cfg(all(target_os = "vexos", feature = "backtrace"))]
+        if let Some(dialog) = &mut dialog {
             dialog
                 .write_str("stack backtrace (check terminal):\n")
                 .unwrap();
-            dialog.write_backtrace(BacktraceIter::new(cursor.clone()));
-
-            eprintln!("stack backtrace:");
-            for (i, frame) in BacktraceIter::new(cursor).enumerate() {
-                eprintln!("{i:>3}: 0x{frame:x}");
-            }
-            eprintln!(
-                "note: Use a symbolizer to convert stack frames to human-readable function names."
-            );
-
-            Ok(())
-        });
+            dialog.write_backtrace(backtrace.frames().iter().map(|frame| frame.addr() as u32));
+        }
+
+        eprintln!("{backtrace}");
+    }
+
+    #[cfg(feature = "crash-report")]
+    {
+        let mut report = CrashReport::new(CrashKind::Panic, info.to_string());
+        report.backtrace.clone_from(&backtrace);
+        report.save();
+    }
+
+    if let Some(on_panic) = behavior.on_panic {
+        on_panic(info, &backtrace);
     }
 
     // Don't exit the program, since we want to be able to see the panic message on the screen.
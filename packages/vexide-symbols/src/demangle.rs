@@ -0,0 +1,131 @@
+//! Demangling for Rust's legacy symbol mangling scheme.
+
+/// Demangles a legacy Rust symbol name, such as `_ZN4core9panicking5panic17h0123456789abcdefE`
+/// into `core::panicking::panic`.
+///
+/// Names that aren't mangled with the legacy scheme, including those mangled with the v0 scheme,
+/// are returned unchanged.
+pub(crate) fn demangle(name: &str) -> String {
+    try_demangle(name).unwrap_or_else(|| name.to_owned())
+}
+
+fn try_demangle(name: &str) -> Option<String> {
+    let mut rest = name
+        .strip_prefix("_ZN")
+        .or_else(|| name.strip_prefix("__ZN"))?;
+
+    let mut components = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let len = rest[..digits].parse::<usize>().ok()?;
+        let component = rest.get(digits..digits + len)?;
+        components.push(component);
+        rest = &rest[digits + len..];
+    }
+
+    // The last component is a hash that distinguishes between different instances of the same
+    // function, which isn't useful in a backtrace.
+    if let Some(hash) = components.last()
+        && hash.len() == 17
+        && hash.starts_with('h')
+        && hash[1..].bytes().all(|byte| byte.is_ascii_hexdigit())
+    {
+        components.pop();
+    }
+
+    let mut demangled = String::new();
+    for (index, component) in components.into_iter().enumerate() {
+        if index > 0 {
+            demangled.push_str("::");
+        }
+        unescape(component, &mut demangled)?;
+    }
+
+    Some(demangled)
+}
+
+/// Writes a path component to `out`, replacing the escape sequences that the legacy scheme uses
+/// for characters that aren't allowed in symbol names.
+fn unescape(mut component: &str, out: &mut String) -> Option<()> {
+    // Components that would start with `$` are prefixed with an underscore.
+    if component.starts_with("_$") {
+        component = &component[1..];
+    }
+
+    while !component.is_empty() {
+        if let Some(rest) = component.strip_prefix("..") {
+            out.push_str("::");
+            component = rest;
+        } else if let Some(rest) = component.strip_prefix('$') {
+            let end = rest.find('$')?;
+            let escape = &rest[..end];
+            out.push(match escape {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                _ => {
+                    let code = escape.strip_prefix('u')?;
+                    char::from_u32(u32::from_str_radix(code, 16).ok()?)?
+                }
+            });
+            component = &rest[end + 1..];
+        } else {
+            // Copy everything up to the next possible escape sequence.
+            let end = component[1..]
+                .find(['$', '.'])
+                .map_or(component.len(), |end| end + 1);
+            out.push_str(&component[..end]);
+            component = &component[end..];
+        }
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strips_hash() {
+        assert_eq!(
+            demangle("_ZN4core9panicking5panic17h0123456789abcdefE"),
+            "core::panicking::panic"
+        );
+    }
+
+    #[test]
+    fn unescapes_components() {
+        assert_eq!(
+            demangle(
+                "_ZN60_$LT$alloc..string..String$u20$as$u20$core..fmt..Display$GT$3fmt17h0123456789abcdefE"
+            ),
+            "<alloc::string::String as core::fmt::Display>::fmt"
+        );
+        assert_eq!(
+            demangle("_ZN4main28_$u7b$$u7b$closure$u7d$$u7d$17h0123456789abcdefE"),
+            "main::{{closure}}"
+        );
+    }
+
+    #[test]
+    fn ignores_suffixes() {
+        assert_eq!(
+            demangle("_ZN4main3run17h0123456789abcdefE.llvm.1234"),
+            "main::run"
+        );
+    }
+
+    #[test]
+    fn leaves_other_names_unchanged() {
+        assert_eq!(demangle("memcpy"), "memcpy");
+        assert_eq!(demangle("_RNvCs1234_4main3run"), "_RNvCs1234_4main3run");
+        assert_eq!(demangle("_ZN4main"), "_ZN4main");
+        assert_eq!(demangle("_ZN99mainE"), "_ZN99mainE");
+    }
+}
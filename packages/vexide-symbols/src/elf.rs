@@ -0,0 +1,179 @@
+//! Minimal reader for the 32-bit little-endian ELF files produced when linking vexide programs.
+
+use std::ops::Range;
+
+use crate::EmbedError;
+
+const HEADER_SIZE: usize = 52;
+const SECTION_HEADER_SIZE: usize = 40;
+const SYMBOL_SIZE: usize = 16;
+
+const CLASS_32: u8 = 1;
+const DATA_LITTLE_ENDIAN: u8 = 1;
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_NOBITS: u32 = 8;
+const STT_FUNC: u8 = 2;
+const SHN_UNDEF: u16 = 0;
+
+/// A section header in an ELF file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Section {
+    name: u32,
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+}
+
+impl Section {
+    /// Returns the range of the file containing the section's data, if it has any.
+    pub(crate) fn data(&self) -> Option<Range<usize>> {
+        (self.kind != SHT_NOBITS).then(|| self.offset..self.offset + self.size)
+    }
+}
+
+/// A function symbol in an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Function {
+    pub(crate) address: u32,
+    pub(crate) size: u32,
+    pub(crate) name: String,
+}
+
+/// A parsed ELF file.
+pub(crate) struct Elf<'a> {
+    bytes: &'a [u8],
+    sections: Vec<Section>,
+    section_names: Section,
+}
+
+impl<'a> Elf<'a> {
+    pub(crate) fn parse(bytes: &'a [u8]) -> Result<Self, EmbedError> {
+        if bytes.get(..4) != Some(b"\x7fELF")
+            || bytes.get(4) != Some(&CLASS_32)
+            || bytes.get(5) != Some(&DATA_LITTLE_ENDIAN)
+        {
+            return Err(EmbedError::NotElf);
+        }
+        if bytes.len() < HEADER_SIZE {
+            return Err(EmbedError::Malformed);
+        }
+
+        let section_offset = read_u32(bytes, 0x20)? as usize;
+        let section_count = read_u16(bytes, 0x30)? as usize;
+        let section_names_index = read_u16(bytes, 0x32)? as usize;
+
+        let sections = (0..section_count)
+            .map(|index| {
+                let header = section_offset + index * SECTION_HEADER_SIZE;
+                let section = Section {
+                    name: read_u32(bytes, header)?,
+                    kind: read_u32(bytes, header + 4)?,
+                    offset: read_u32(bytes, header + 16)? as usize,
+                    size: read_u32(bytes, header + 20)? as usize,
+                    link: read_u32(bytes, header + 24)?,
+                };
+
+                // Make sure that every section's data is actually in the file.
+                if let Some(data) = section.data()
+                    && data.end > bytes.len()
+                {
+                    return Err(EmbedError::Malformed);
+                }
+
+                Ok(section)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let section_names = *sections
+            .get(section_names_index)
+            .ok_or(EmbedError::Malformed)?;
+
+        Ok(Self {
+            bytes,
+            sections,
+            section_names,
+        })
+    }
+
+    /// Returns the section with the given name, if there is one.
+    pub(crate) fn section(&self, name: &str) -> Option<Section> {
+        self.sections.iter().copied().find(|section| {
+            string(self.bytes, self.section_names, section.name) == Some(name.as_bytes())
+        })
+    }
+
+    /// Returns every function defined in the file's symbol table, with demangled names.
+    ///
+    /// Functions without a size are skipped, as are any that share an address with a function
+    /// that came before them.
+    pub(crate) fn functions(&self) -> Result<Vec<Function>, EmbedError> {
+        let symtab = self
+            .sections
+            .iter()
+            .find(|section| section.kind == SHT_SYMTAB)
+            .ok_or(EmbedError::MissingSymbols)?;
+        let strtab = *self
+            .sections
+            .get(symtab.link as usize)
+            .ok_or(EmbedError::Malformed)?;
+        let symbols = self
+            .bytes
+            .get(symtab.data().ok_or(EmbedError::Malformed)?)
+            .ok_or(EmbedError::Malformed)?;
+
+        let mut functions: Vec<Function> = Vec::new();
+        for symbol in symbols.chunks_exact(SYMBOL_SIZE) {
+            let name = read_u32(symbol, 0)?;
+            let value = read_u32(symbol, 4)?;
+            let size = read_u32(symbol, 8)?;
+            let info = symbol[12];
+            let section_index = read_u16(symbol, 14)?;
+
+            if info & 0xf != STT_FUNC || section_index == SHN_UNDEF || size == 0 {
+                continue;
+            }
+
+            let name = string(self.bytes, strtab, name).ok_or(EmbedError::Malformed)?;
+            let Ok(name) = std::str::from_utf8(name) else {
+                continue;
+            };
+
+            functions.push(Function {
+                // The lowest bit of a function's address is set if it uses the Thumb instruction
+                // set, but return addresses in backtraces don't include it.
+                address: value & !1,
+                size,
+                name: crate::demangle::demangle(name),
+            });
+        }
+
+        functions.sort_by_key(|function| function.address);
+        functions.dedup_by_key(|function| function.address);
+
+        Ok(functions)
+    }
+}
+
+/// Reads the null-terminated string at `offset` in a string table section.
+fn string(bytes: &[u8], table: Section, offset: u32) -> Option<&[u8]> {
+    let strings = bytes.get(table.data()?)?;
+    let string = strings.get(offset as usize..)?;
+    let end = string.iter().position(|&byte| byte == 0)?;
+    Some(&string[..end])
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, EmbedError> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or(EmbedError::Malformed)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, EmbedError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or(EmbedError::Malformed)
+}
@@ -0,0 +1,275 @@
+//! Embeds a [`SymbolTable`] into a linked vexide program, so that backtraces printed on the brain
+//! include function names.
+//!
+//! When vexide is built with its `symbols` feature, space for a symbol table is reserved in the
+//! program's `.vexide_symbols` link section. The addresses of the program's functions are only
+//! known once it has been linked, so this crate reads them from the program's ELF file and writes
+//! the table into the reserved section. This must be done before the ELF file is converted into the
+//! binary that's uploaded to the brain.
+//!
+//! The `vexide-symbols` binary does this for the ELF file at the given path:
+//!
+//! ```sh
+//! vexide-symbols target/armv7a-vex-v5/release/my-program
+//! ```
+//!
+//! Other build tools can use [`embed`] directly.
+//!
+//! [`SymbolTable`]: vexide_core::backtrace::SymbolTable
+
+use std::{error::Error, fmt};
+
+use vexide_core::backtrace::SymbolTable;
+
+mod demangle;
+mod elf;
+
+/// The name of the link section that the symbol table is written to.
+pub const SECTION: &str = ".vexide_symbols";
+
+/// Information about a symbol table written by [`embed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Embedded {
+    /// The number of functions in the table.
+    pub functions: usize,
+
+    /// The size of the table in bytes.
+    pub size: usize,
+
+    /// The size of the reserved section in bytes.
+    pub capacity: usize,
+}
+
+/// Writes a symbol table containing the functions in `elf` to its `.vexide_symbols` section.
+///
+/// `elf` is the contents of a linked vexide program's ELF file, which is modified in place. The
+/// rest of the section is zeroed, so this can be run again on a program that already has a table.
+///
+/// # Errors
+///
+/// - [`EmbedError::NotElf`] is returned if `elf` isn't a 32-bit little-endian ELF file.
+/// - [`EmbedError::Malformed`] is returned if the ELF file is truncated or otherwise invalid.
+/// - [`EmbedError::MissingSection`] is returned if the program doesn't have a `.vexide_symbols`
+///   section, such as if vexide's `symbols` feature is disabled.
+/// - [`EmbedError::MissingSymbols`] is returned if the program doesn't have a symbol table, such as
+///   if it was stripped.
+/// - [`EmbedError::TooLarge`] is returned if the table doesn't fit in the reserved section.
+pub fn embed(elf: &mut [u8]) -> Result<Embedded, EmbedError> {
+    let parsed = elf::Elf::parse(elf)?;
+    let section = parsed
+        .section(SECTION)
+        .and_then(|section| section.data())
+        .ok_or(EmbedError::MissingSection)?;
+    let functions = parsed.functions()?;
+
+    let table = SymbolTable::encode(
+        functions
+            .iter()
+            .map(|function| (function.address, function.size, function.name.as_str())),
+    );
+
+    let capacity = section.len();
+    if table.len() > capacity {
+        return Err(EmbedError::TooLarge {
+            size: table.len(),
+            capacity,
+        });
+    }
+
+    let data = &mut elf[section];
+    data[..table.len()].copy_from_slice(&table);
+    data[table.len()..].fill(0);
+
+    Ok(Embedded {
+        functions: functions.len(),
+        size: table.len(),
+        capacity,
+    })
+}
+
+/// An error that can occur when embedding a symbol table with [`embed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmbedError {
+    /// The file isn't a 32-bit little-endian ELF file.
+    NotElf,
+
+    /// The ELF file is truncated or otherwise invalid.
+    Malformed,
+
+    /// The program doesn't have a `.vexide_symbols` section.
+    MissingSection,
+
+    /// The program doesn't have a symbol table.
+    MissingSymbols,
+
+    /// The symbol table is larger than the reserved section.
+    TooLarge {
+        /// The size of the table in bytes.
+        size: usize,
+
+        /// The size of the reserved section in bytes.
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for EmbedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotElf => write!(f, "file is not a 32-bit little-endian ELF file"),
+            Self::Malformed => write!(f, "ELF file is truncated or malformed"),
+            Self::MissingSection => write!(
+                f,
+                "program has no `{SECTION}` section (is vexide's `symbols` feature enabled?)"
+            ),
+            Self::MissingSymbols => write!(f, "program has no symbol table (was it stripped?)"),
+            Self::TooLarge { size, capacity } => write!(
+                f,
+                "symbol table is {size} bytes, but only {capacity} bytes are reserved for it"
+            ),
+        }
+    }
+}
+
+impl Error for EmbedError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SYMBOLS_SIZE: usize = 256;
+
+    /// Builds an ELF file with a `.vexide_symbols` section and a symbol table containing the given
+    /// symbols, each of which is a name, value, size, and type.
+    fn build_elf(symbols: &[(&str, u32, u32, u8)], symbols_size: usize) -> Vec<u8> {
+        fn push_u16(bytes: &mut Vec<u8>, n: u16) {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        fn push_u32(bytes: &mut Vec<u8>, n: u32) {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+
+        let section_names = b"\0.vexide_symbols\0.symtab\0.strtab\0.shstrtab\0";
+
+        let mut strtab = vec![0];
+        let mut symtab = vec![0; 16];
+        for &(name, value, size, kind) in symbols {
+            push_u32(&mut symtab, strtab.len() as u32);
+            push_u32(&mut symtab, value);
+            push_u32(&mut symtab, size);
+            symtab.extend_from_slice(&[kind, 0]);
+            push_u16(&mut symtab, 1);
+            strtab.extend_from_slice(name.as_bytes());
+            strtab.push(0);
+        }
+
+        // The section data follows the ELF header, and the section headers come last.
+        let sections: [(u32, u32, &[u8], u32); 4] = [
+            (1, 1, &vec![0; symbols_size], 0),
+            (17, 2, &symtab, 3),
+            (25, 3, &strtab, 0),
+            (33, 3, section_names, 0),
+        ];
+
+        let mut data = Vec::new();
+        let mut headers = vec![0; 40];
+        for (name, kind, contents, link) in sections {
+            push_u32(&mut headers, name);
+            push_u32(&mut headers, kind);
+            push_u32(&mut headers, 0);
+            push_u32(&mut headers, 0);
+            push_u32(&mut headers, (52 + data.len()) as u32);
+            push_u32(&mut headers, contents.len() as u32);
+            push_u32(&mut headers, link);
+            push_u32(&mut headers, 0);
+            push_u32(&mut headers, 0);
+            push_u32(&mut headers, 0);
+            data.extend_from_slice(contents);
+        }
+
+        let mut elf = b"\x7fELF\x01\x01\x01".to_vec();
+        elf.resize(0x20, 0);
+        push_u32(&mut elf, (52 + data.len()) as u32);
+        elf.resize(0x30, 0);
+        push_u16(&mut elf, 5);
+        push_u16(&mut elf, 4);
+        elf.extend_from_slice(&data);
+        elf.extend_from_slice(&headers);
+        elf
+    }
+
+    fn embedded_table(elf: &[u8]) -> SymbolTable<'_> {
+        let section = elf::Elf::parse(elf).unwrap().section(SECTION).unwrap();
+        SymbolTable::parse(&elf[section.data().unwrap()]).unwrap()
+    }
+
+    #[test]
+    fn embeds_functions() {
+        let mut elf = build_elf(
+            &[
+                ("_ZN4main3run17h0123456789abcdefE", 0x0380_0101, 0x40, 2),
+                ("memcpy", 0x0380_0200, 0x20, 2),
+                ("DATA", 0x0380_0300, 0x20, 1),
+                ("empty", 0x0380_0400, 0, 2),
+            ],
+            SYMBOLS_SIZE,
+        );
+
+        let embedded = embed(&mut elf).unwrap();
+        assert_eq!(embedded.functions, 2);
+        assert_eq!(embedded.capacity, SYMBOLS_SIZE);
+
+        let table = embedded_table(&elf);
+        assert_eq!(table.len(), 2);
+
+        let symbol = table.lookup(0x0380_0110).unwrap();
+        assert_eq!(symbol.name(), "main::run");
+        assert_eq!(symbol.address(), 0x0380_0100);
+        assert_eq!(table.lookup(0x0380_0210).unwrap().name(), "memcpy");
+        assert_eq!(table.lookup(0x0380_0310), None);
+    }
+
+    #[test]
+    fn replaces_existing_table() {
+        let mut elf = build_elf(
+            &[("a_function_with_a_long_name", 0x100, 0x20, 2)],
+            SYMBOLS_SIZE,
+        );
+        embed(&mut elf).unwrap();
+        let first = embedded_table(&elf).len();
+
+        let embedded = embed(&mut elf).unwrap();
+        assert_eq!(embedded_table(&elf).len(), first);
+
+        let section = elf::Elf::parse(&elf).unwrap().section(SECTION).unwrap();
+        assert!(
+            elf[section.data().unwrap()][embedded.size..]
+                .iter()
+                .all(|&byte| byte == 0)
+        );
+    }
+
+    #[test]
+    fn rejects_large_tables() {
+        let mut elf = build_elf(&[("main", 0x100, 0x20, 2)], 8);
+        assert_eq!(
+            embed(&mut elf),
+            Err(EmbedError::TooLarge {
+                size: 36,
+                capacity: 8
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert_eq!(
+            embed(&mut b"not an elf file".to_vec()),
+            Err(EmbedError::NotElf)
+        );
+
+        let mut elf = build_elf(&[], SYMBOLS_SIZE);
+        elf.truncate(100);
+        assert_eq!(embed(&mut elf), Err(EmbedError::Malformed));
+    }
+}
@@ -0,0 +1,41 @@
+//! Embeds a symbol table into the vexide program at the given path.
+
+use std::{env, fs, process::ExitCode};
+
+fn main() -> ExitCode {
+    let mut args = env::args_os().skip(1);
+    let (Some(path), None) = (args.next(), args.next()) else {
+        eprintln!("usage: vexide-symbols <ELF FILE>");
+        return ExitCode::FAILURE;
+    };
+
+    let mut elf = match fs::read(&path) {
+        Ok(elf) => elf,
+        Err(err) => {
+            eprintln!("error: failed to read {}: {err}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let embedded = match vexide_symbols::embed(&mut elf) {
+        Ok(embedded) => embedded,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(err) = fs::write(&path, elf) {
+        eprintln!("error: failed to write {}: {err}", path.display());
+        return ExitCode::FAILURE;
+    }
+
+    println!(
+        "Embedded {} functions into {} ({} of {} bytes)",
+        embedded.functions,
+        path.display(),
+        embedded.size,
+        embedded.capacity,
+    );
+    ExitCode::SUCCESS
+}
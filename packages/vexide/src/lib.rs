@@ -41,7 +41,7 @@ pub mod runtime {
 pub use vexide_async::sync;
 #[doc(inline)]
 #[cfg(feature = "async")]
-pub use vexide_async::task;
+pub use vexide_async::{future, stream, task};
 
 /// Utilities for tracking time.
 ///
@@ -52,9 +52,21 @@ pub use vexide_async::task;
 /// - [`sleep`] and [`sleep_until`] provide ways to yield control away from a future for or until a
 ///   specific instant in time.
 ///
+/// - [`timeout`] and [`timeout_at`] limit the amount of time that a future is allowed to run for.
+///
+/// - [`Interval`] yields at a fixed period, which is useful for running control loops at a
+///   consistent rate.
+///
+/// - [`now`] returns the current time according to the runtime's clock, which can be paused and
+///   advanced in tests using the `test-util` feature.
+///
 /// [`Instant`]: std::time::Instant
 /// [`sleep`]: vexide_async::time::sleep
 /// [`sleep_until`]: vexide_async::time::sleep_until
+/// [`timeout`]: vexide_async::time::timeout
+/// [`timeout_at`]: vexide_async::time::timeout_at
+/// [`Interval`]: vexide_async::time::Interval
+/// [`now`]: vexide_async::time::now
 #[cfg(any(feature = "core", feature = "async"))]
 pub mod time {
     #[doc(inline)]
@@ -73,7 +85,9 @@ pub use vexide_core::backtrace;
 pub use vexide_core::{competition, os, program};
 #[doc(inline)]
 #[cfg(feature = "devices")]
-pub use vexide_devices::{adi, battery, color, controller, display, math, peripherals, smart};
+pub use vexide_devices::{
+    adi, battery, color, controller, display, math, peripherals, safe_state, smart,
+};
 #[doc(inline)]
 #[cfg(all(
     feature = "macros",
@@ -146,8 +160,9 @@ pub mod prelude {
     };
     #[cfg(feature = "async")]
     pub use crate::{
+        future::{join, select},
         runtime::block_on,
         task::{spawn, task_local},
-        time::{sleep, sleep_until},
+        time::{sleep, sleep_until, timeout},
     };
 }